@@ -0,0 +1,119 @@
+use super::runtime::Error;
+
+/// Defines how operations on `int` behave when their result does not fit into an `int`
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Arithmetic {
+    /// Overflows are runtime errors, the default one
+    #[default]
+    Checked,
+    /// Results wrap around at the bounds of `int`
+    Wrapping,
+    /// Results are clamped to the bounds of `int`
+    Saturating,
+}
+
+/// Operation applied by arithmetic and bitwise instructions, e.g. `add`, `cadd` and `and`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    And,
+    Or,
+    Xor,
+    Shl,
+    Shr,
+}
+
+impl Operation {
+    /// Applies the operation on two `int`, following the given policy.
+    ///
+    /// Division and modulo by zero, and shifts by a negative amount or by more than 31 bits, are
+    /// errors whatever the policy is. Bitwise operations cannot overflow, and `shr` keeps the sign.
+    pub fn int(self, a: i32, b: i32, policy: Arithmetic, lnb: usize) -> Result<i32, Error> {
+        match self {
+            Operation::Div if b == 0 => return Err(Error::DivisionByZero(lnb)),
+            Operation::Mod if b == 0 => return Err(Error::ModuloByZero(lnb)),
+            Operation::Shl | Operation::Shr if !(0..32).contains(&b) => {
+                return Err(Error::ShiftOutOfRange(b, lnb))
+            }
+            Operation::And => return Ok(a & b),
+            Operation::Or => return Ok(a | b),
+            Operation::Xor => return Ok(a ^ b),
+            Operation::Shl => return Ok(a << b),
+            Operation::Shr => return Ok(a >> b),
+            _ => (),
+        }
+        let result = match (policy, self) {
+            (Arithmetic::Checked, Operation::Add) => a.checked_add(b),
+            (Arithmetic::Checked, Operation::Sub) => a.checked_sub(b),
+            (Arithmetic::Checked, Operation::Mul) => a.checked_mul(b),
+            (Arithmetic::Checked, Operation::Div) => a.checked_div(b),
+            (Arithmetic::Checked, Operation::Mod) => a.checked_rem(b),
+            (Arithmetic::Wrapping, Operation::Add) => Some(a.wrapping_add(b)),
+            (Arithmetic::Wrapping, Operation::Sub) => Some(a.wrapping_sub(b)),
+            (Arithmetic::Wrapping, Operation::Mul) => Some(a.wrapping_mul(b)),
+            (Arithmetic::Wrapping, Operation::Div) => Some(a.wrapping_div(b)),
+            (Arithmetic::Wrapping, Operation::Mod) => Some(a.wrapping_rem(b)),
+            (Arithmetic::Saturating, Operation::Add) => Some(a.saturating_add(b)),
+            (Arithmetic::Saturating, Operation::Sub) => Some(a.saturating_sub(b)),
+            (Arithmetic::Saturating, Operation::Mul) => Some(a.saturating_mul(b)),
+            (Arithmetic::Saturating, Operation::Div) => Some(a.saturating_div(b)),
+            // The only overflowing case, `int::MIN % -1`, is 0
+            (Arithmetic::Saturating, Operation::Mod) => Some(a.wrapping_rem(b)),
+            (
+                _,
+                Operation::And | Operation::Or | Operation::Xor | Operation::Shl | Operation::Shr,
+            ) => {
+                unreachable!("bitwise operations are applied above")
+            }
+        };
+        match result {
+            Some(result) => Ok(result),
+            None => Err(Error::IntegerOverflow(lnb)),
+        }
+    }
+
+    /// Applies the operation on two `flt`, following IEEE 754 rules.
+    ///
+    /// Bitwise operations are errors, as they only apply on `int`.
+    pub fn flt(self, a: f64, b: f64, lnb: usize) -> Result<f64, Error> {
+        match self {
+            Operation::Add => Ok(a + b),
+            Operation::Sub => Ok(a - b),
+            Operation::Mul => Ok(a * b),
+            Operation::Div => Ok(a / b),
+            Operation::Mod => Ok(a % b),
+            Operation::And | Operation::Or | Operation::Xor | Operation::Shl | Operation::Shr => {
+                Err(Error::CannotApplyBitwiseOnFlt(lnb))
+            }
+        }
+    }
+}
+
+/// Comparison applied by comparison instructions and conditional jumps, e.g. `clt` and `jlt`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    /// Returns `true` if the comparison holds, `chr` being compared by code point
+    pub fn holds<T: PartialOrd>(self, a: T, b: T) -> bool {
+        match self {
+            Comparison::Eq => a == b,
+            Comparison::Ne => a != b,
+            Comparison::Lt => a < b,
+            Comparison::Le => a <= b,
+            Comparison::Gt => a > b,
+            Comparison::Ge => a >= b,
+        }
+    }
+}
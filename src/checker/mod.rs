@@ -0,0 +1,451 @@
+use super::instructions::*;
+use super::runtime::Error;
+use super::ProgramFile;
+use std::collections::HashMap;
+
+impl ProgramFile {
+    /// Checks the types of the parsed program, without running it.
+    ///
+    /// Every path the program can take is followed, from its first line, to find the variables
+    /// existing at each line and their possible types. Reports, sorted by line :
+    /// - use of variables that may not exist, as `VariableDoesNotExists`
+    /// - operands that may differ in type, as `VariablesDifferInType`, or as
+    ///   `CouldNotParse*Value` and `CannotDetermineReturnType` for hardcoded values
+    /// - arithmetic on `chr`, as `CannotApplyOperationsOnChar`
+    /// - bitwise operations on `flt`, as `CannotApplyBitwiseOnFlt`
+    /// - conversions from or into a variable of another type, as `VariablesDifferInType`
+    /// - `jmp` and `jne` on `chr`, as `CannotApplyComparisonsOnChar`
+    /// - comparisons between two hardcoded values, as `CannotDetermineReturnType`
+    ///
+    /// A program passing the check cannot fail at runtime with any of those errors.
+    pub fn check(&self) -> Result<(), Vec<Error>> {
+        let checker = Checker::new(self);
+        let states = checker.states();
+        let mut errors = Vec::new();
+        for (lnb, state) in states.into_iter().enumerate() {
+            // Lines no path goes through cannot fail
+            if let Some(mut state) = state {
+                checker.transfer(lnb, &mut state, &mut errors);
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// What is known before executing a line, whatever path led to it
+#[derive(Debug, PartialEq, Clone)]
+struct State {
+    /// Possible types of each variable existing on every path, variables missing may not exist
+    vars: HashMap<String, Vec<Type>>,
+    /// Types of the values on the stack, `None` if it depends on the path
+    stack: Option<Vec<Type>>,
+}
+
+impl State {
+    /// Returns what is known after either of two paths
+    fn join(&self, other: &State) -> State {
+        let vars = self
+            .vars
+            .iter()
+            .filter_map(|(name, types)| {
+                let mut types = types.clone();
+                for t in other.vars.get(name)? {
+                    if !types.contains(t) {
+                        types.push(*t);
+                    }
+                }
+                Some((name.clone(), types))
+            })
+            .collect();
+        let stack = if self.stack == other.stack {
+            self.stack.clone()
+        } else {
+            None
+        };
+        State { vars, stack }
+    }
+}
+
+struct Checker<'a> {
+    file: &'a ProgramFile,
+    /// Lines following a `cal`, where `ret` can return to
+    returns: Vec<usize>,
+    /// Possible types of any value pushed on the stack
+    pushed: Vec<Type>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(file: &'a ProgramFile) -> Self {
+        let mut declared: HashMap<&str, Vec<Type>> = HashMap::new();
+        let mut returns = Vec::new();
+        for (lnb, line) in file.lines.iter().enumerate() {
+            match &line.instruction {
+                Instruction::Var { var, var_type } => {
+                    declared.entry(&var.name).or_default().push(*var_type)
+                }
+                Instruction::Cal { .. } => returns.push(lnb + 1),
+                _ => (),
+            }
+        }
+        let mut pushed = Vec::new();
+        for line in file.lines.iter() {
+            let types = match &line.instruction {
+                Instruction::Push {
+                    value: Val::Const(val),
+                } => vec![val.type_of()],
+                Instruction::Push {
+                    value: Val::Var(var),
+                } => declared.get(var.name.as_str()).cloned().unwrap_or_default(),
+                _ => continue,
+            };
+            for t in types {
+                if !pushed.contains(&t) {
+                    pushed.push(t);
+                }
+            }
+        }
+        Self {
+            file,
+            returns,
+            pushed,
+        }
+    }
+
+    /// Returns what is known before each line, `None` for lines no path goes through
+    fn states(&self) -> Vec<Option<State>> {
+        let mut states = vec![None; self.file.lines.len()];
+        if states.is_empty() {
+            return states;
+        }
+        states[0] = Some(State {
+            vars: HashMap::new(),
+            stack: Some(Vec::new()),
+        });
+        let mut pending = vec![0];
+        while let Some(lnb) = pending.pop() {
+            let mut state = match &states[lnb] {
+                Some(state) => state.clone(),
+                None => continue,
+            };
+            self.transfer(lnb, &mut state, &mut Vec::new());
+            for next in self.successors(lnb) {
+                if next >= states.len() {
+                    continue;
+                }
+                let joined = match &states[next] {
+                    Some(old) => old.join(&state),
+                    None => state.clone(),
+                };
+                if states[next].as_ref() != Some(&joined) {
+                    states[next] = Some(joined);
+                    pending.push(next);
+                }
+            }
+        }
+        states
+    }
+
+    /// Returns the lines that can be executed after a line
+    fn successors(&self, lnb: usize) -> Vec<usize> {
+        let line = &self.file.lines[lnb].instruction;
+        let target = line
+            .flag()
+            .and_then(|flag| self.file.flags.get(&flag.name).copied());
+        match line {
+            Instruction::Gto { .. } | Instruction::Cal { .. } => target.into_iter().collect(),
+            Instruction::Ret => self.returns.clone(),
+            _ => std::iter::once(lnb + 1).chain(target).collect(),
+        }
+    }
+
+    /// Updates `state` with the effects of a line, and reports the errors it may raise
+    fn transfer(&self, lnb: usize, state: &mut State, errors: &mut Vec<Error>) {
+        match &self.file.lines[lnb].instruction {
+            Instruction::Var { var, var_type } => {
+                state.vars.insert(var.name.clone(), vec![*var_type]);
+            }
+            Instruction::Set { var, value } => {
+                if let Some(types) = lookup(state, var, lnb, errors) {
+                    check_source(state, &types, value, lnb, errors);
+                }
+            }
+            Instruction::Add { var, value }
+            | Instruction::Sub { var, value }
+            | Instruction::Mul { var, value }
+            | Instruction::Div { var, value }
+            | Instruction::Mod { var, value } => {
+                if let Some(types) = lookup(state, var, lnb, errors) {
+                    if types.contains(&Type::Chr) {
+                        errors.push(Error::CannotApplyOperationsOnChar(lnb));
+                    } else {
+                        check_source(state, &types, value, lnb, errors);
+                    }
+                }
+            }
+            Instruction::Cadd { op1, op2 }
+            | Instruction::Csub { op1, op2 }
+            | Instruction::Cmul { op1, op2 }
+            | Instruction::Cdiv { op1, op2 }
+            | Instruction::Cmod { op1, op2 } => {
+                if let Some(types) = carry_types(state, op1, op2, lnb, errors) {
+                    state.vars.insert(crate::CARRY_VARIABLE.to_owned(), types);
+                }
+            }
+            Instruction::And { var, value }
+            | Instruction::Or { var, value }
+            | Instruction::Xor { var, value }
+            | Instruction::Shl { var, value }
+            | Instruction::Shr { var, value } => {
+                if let Some(types) = lookup(state, var, lnb, errors) {
+                    match bitwise_error(&types, lnb) {
+                        Some(e) => errors.push(e),
+                        None => check_source(state, &types, value, lnb, errors),
+                    }
+                }
+            }
+            Instruction::Not { var } => {
+                if let Some(types) = lookup(state, var, lnb, errors) {
+                    errors.extend(bitwise_error(&types, lnb));
+                }
+            }
+            Instruction::Cand { op1, op2 }
+            | Instruction::Cor { op1, op2 }
+            | Instruction::Cxor { op1, op2 }
+            | Instruction::Cshl { op1, op2 }
+            | Instruction::Cshr { op1, op2 } => {
+                if let Some(types) = carry_types(state, op1, op2, lnb, errors) {
+                    // Operations on `chr` are already reported
+                    if types.contains(&Type::Flt) {
+                        errors.push(Error::CannotApplyBitwiseOnFlt(lnb));
+                    }
+                    state.vars.insert(crate::CARRY_VARIABLE.to_owned(), types);
+                }
+            }
+            Instruction::Cnot { value } => {
+                let types = match value {
+                    Val::Const(val) => Some(vec![val.type_of()]),
+                    Val::Var(var) => lookup(state, var, lnb, errors),
+                };
+                if let Some(types) = types {
+                    errors.extend(bitwise_error(&types, lnb));
+                }
+                state
+                    .vars
+                    .insert(crate::CARRY_VARIABLE.to_owned(), vec![Type::Int]);
+            }
+            Instruction::Add3 { var, op1, op2 }
+            | Instruction::Sub3 { var, op1, op2 }
+            | Instruction::Mul3 { var, op1, op2 }
+            | Instruction::Div3 { var, op1, op2 }
+            | Instruction::Mod3 { var, op1, op2 } => {
+                let result = carry_types(state, op1, op2, lnb, errors);
+                let types = lookup(state, var, lnb, errors);
+                if let (Some(result), Some(types)) = (result, types) {
+                    // Operations on `chr` are already reported
+                    if !result.contains(&Type::Chr) && !same_type(&types, &result) {
+                        errors.push(Error::VariablesDifferInType(lnb));
+                    }
+                }
+            }
+            Instruction::Itf { var, .. }
+            | Instruction::Fti { var, .. }
+            | Instruction::Cti { var, .. }
+            | Instruction::Itc { var, .. } => {
+                if let Some((value, from, to)) = self.file.lines[lnb].instruction.conversion() {
+                    check_source(state, &[from], value, lnb, errors);
+                    if let Some(types) = lookup(state, var, lnb, errors) {
+                        if !same_type(&types, &[to]) {
+                            errors.push(Error::VariablesDifferInType(lnb));
+                        }
+                    }
+                }
+            }
+            Instruction::Del { var } => {
+                lookup(state, var, lnb, errors);
+                state.vars.remove(&var.name);
+            }
+            Instruction::Inc { var } | Instruction::Dec { var } => {
+                if let Some(types) = lookup(state, var, lnb, errors) {
+                    if types.contains(&Type::Chr) {
+                        errors.push(Error::CannotApplyOperationsOnChar(lnb));
+                    }
+                }
+            }
+            Instruction::Jmp { var, .. } | Instruction::Jne { var, .. } => {
+                if let Some(types) = lookup(state, var, lnb, errors) {
+                    if types.contains(&Type::Chr) {
+                        errors.push(Error::CannotApplyComparisonsOnChar(lnb));
+                    }
+                }
+            }
+            Instruction::Jeq { op1, op2, .. }
+            | Instruction::Jneq { op1, op2, .. }
+            | Instruction::Jlt { op1, op2, .. }
+            | Instruction::Jle { op1, op2, .. }
+            | Instruction::Jgt { op1, op2, .. }
+            | Instruction::Jge { op1, op2, .. } => check_comparison(state, op1, op2, lnb, errors),
+            Instruction::Ceq { op1, op2 }
+            | Instruction::Cne { op1, op2 }
+            | Instruction::Clt { op1, op2 }
+            | Instruction::Cle { op1, op2 }
+            | Instruction::Cgt { op1, op2 }
+            | Instruction::Cge { op1, op2 } => {
+                check_comparison(state, op1, op2, lnb, errors);
+                state
+                    .vars
+                    .insert(crate::CARRY_VARIABLE.to_owned(), vec![Type::Int]);
+            }
+            Instruction::Prt { value } | Instruction::Yld { value } => {
+                if let Val::Var(var) = value {
+                    lookup(state, var, lnb, errors);
+                }
+            }
+            Instruction::Inp { var } => {
+                lookup(state, var, lnb, errors);
+            }
+            Instruction::Push { value } => {
+                let types = match value {
+                    Val::Const(val) => Some(vec![val.type_of()]),
+                    Val::Var(var) => lookup(state, var, lnb, errors),
+                };
+                state.stack = match (state.stack.take(), types.as_deref()) {
+                    (Some(mut stack), Some([t])) => {
+                        stack.push(*t);
+                        Some(stack)
+                    }
+                    _ => None,
+                };
+            }
+            Instruction::Pop { var } => {
+                let types = lookup(state, var, lnb, errors);
+                let popped = match state.stack.as_mut() {
+                    Some(stack) => stack.pop().map(|t| vec![t]),
+                    None => Some(self.pushed.clone()),
+                };
+                if let (Some(types), Some(popped)) = (types, popped) {
+                    if !same_type(&types, &popped) {
+                        errors.push(Error::VariablesDifferInType(lnb));
+                    }
+                }
+            }
+            Instruction::Gto { .. }
+            | Instruction::Cal { .. }
+            | Instruction::Ret
+            | Instruction::Pas
+            | Instruction::Flg
+            | Instruction::Nll => (),
+        }
+    }
+}
+
+/// Returns the possible types of a variable, reports it if it may not exist
+fn lookup(state: &State, var: &Var, lnb: usize, errors: &mut Vec<Error>) -> Option<Vec<Type>> {
+    match state.vars.get(&var.name) {
+        Some(types) => Some(types.clone()),
+        None => {
+            errors.push(Error::VariableDoesNotExists(var.name.clone(), lnb));
+            None
+        }
+    }
+}
+
+/// Checks that the value given to a variable of the given possible types always fits it
+fn check_source(state: &State, types: &[Type], value: &Val, lnb: usize, errors: &mut Vec<Error>) {
+    match value {
+        Val::Const(val) => {
+            if let Some(t) = types.iter().find(|t| !val.fits(**t)) {
+                errors.push(Error::could_not_parse(*t, val, lnb));
+            }
+        }
+        Val::Var(source) => {
+            if let Some(source) = lookup(state, source, lnb, errors) {
+                if !same_type(types, &source) {
+                    errors.push(Error::VariablesDifferInType(lnb))
+                }
+            }
+        }
+    }
+}
+
+/// Returns `true` if two operands always have the same type
+fn same_type(a: &[Type], b: &[Type]) -> bool {
+    match (a, b) {
+        ([a], [b]) => a == b,
+        (a, []) | ([], a) => a.len() <= 1,
+        _ => false,
+    }
+}
+
+/// Returns the possible types of the result of a carry operation, reports the errors it may raise
+fn carry_types(
+    state: &State,
+    op1: &Val,
+    op2: &Val,
+    lnb: usize,
+    errors: &mut Vec<Error>,
+) -> Option<Vec<Type>> {
+    match (op1, op2) {
+        (Val::Var(var_1), Val::Var(var_2)) => {
+            let types_1 = lookup(state, var_1, lnb, errors);
+            let types_2 = lookup(state, var_2, lnb, errors);
+            let types_1 = types_1?;
+            if types_1.contains(&Type::Chr) {
+                errors.push(Error::CannotApplyOperationsOnChar(lnb));
+            } else if !same_type(&types_1, &types_2?) {
+                errors.push(Error::VariablesDifferInType(lnb));
+            }
+            Some(types_1)
+        }
+        (Val::Var(var), Val::Const(val)) | (Val::Const(val), Val::Var(var)) => {
+            let types = lookup(state, var, lnb, errors)?;
+            if types.contains(&Type::Chr) {
+                errors.push(Error::CannotApplyOperationsOnChar(lnb));
+            } else if types.iter().any(|t| !val.fits(*t)) {
+                errors.push(Error::CannotDetermineReturnType(lnb));
+            }
+            Some(types)
+        }
+        (Val::Const(_), Val::Const(_)) => {
+            errors.push(Error::CannotDetermineReturnType(lnb));
+            None
+        }
+    }
+}
+
+/// Returns the error raised by a bitwise operation on a variable of the given possible types
+fn bitwise_error(types: &[Type], lnb: usize) -> Option<Error> {
+    if types.contains(&Type::Chr) {
+        Some(Error::CannotApplyOperationsOnChar(lnb))
+    } else if types.contains(&Type::Flt) {
+        Some(Error::CannotApplyBitwiseOnFlt(lnb))
+    } else {
+        None
+    }
+}
+
+/// Checks that the operands of a comparison always have the same type
+fn check_comparison(state: &State, op1: &Val, op2: &Val, lnb: usize, errors: &mut Vec<Error>) {
+    match (op1, op2) {
+        (Val::Var(var_1), Val::Var(var_2)) => {
+            let types_1 = lookup(state, var_1, lnb, errors);
+            let types_2 = lookup(state, var_2, lnb, errors);
+            if let (Some(types_1), Some(types_2)) = (types_1, types_2) {
+                if !same_type(&types_1, &types_2) {
+                    errors.push(Error::VariablesDifferInType(lnb));
+                }
+            }
+        }
+        (Val::Var(var), Val::Const(val)) | (Val::Const(val), Val::Var(var)) => {
+            if let Some(types) = lookup(state, var, lnb, errors) {
+                if types.iter().any(|t| !val.fits(*t)) {
+                    errors.push(Error::CannotDetermineReturnType(lnb));
+                }
+            }
+        }
+        (Val::Const(_), Val::Const(_)) => errors.push(Error::CannotDetermineReturnType(lnb)),
+    }
+}
@@ -0,0 +1,418 @@
+use super::instructions::*;
+use super::memory::{Mem, CARRY_SLOT};
+use super::runtime::Error;
+use super::{ProgramFile, CARRY_VARIABLE};
+use std::collections::HashMap;
+
+/// Compiles the parsed lines of a program into the instructions run by `Program` :
+/// - variables are given a memory slot, the carry variable `-` being the first one
+/// - flags are resolved to the line they are created at, unknown flags are errors
+/// - hardcoded values are checked against the declared type of the variables they are used with
+///
+/// Returns the compiled lines, which keep their spans, and the memory holding a slot for each
+/// variable.
+pub(crate) fn compile(file: &ProgramFile) -> Result<(Vec<Line>, Mem), Error> {
+    let mut compiler = Compiler {
+        flags: &file.flags,
+        slots: HashMap::new(),
+        names: Vec::new(),
+        types: HashMap::new(),
+        lnb: 0,
+    };
+    for line in file.lines.iter() {
+        if let Instruction::Var { var, var_type } = &line.instruction {
+            compiler
+                .types
+                .entry(var.name.clone())
+                .and_modify(|declared| {
+                    if declared.as_ref() != Some(var_type) {
+                        *declared = None
+                    }
+                })
+                .or_insert_with(|| Some(*var_type));
+        }
+    }
+    compiler.slot(CARRY_VARIABLE);
+    debug_assert_eq!(compiler.slots[CARRY_VARIABLE], CARRY_SLOT);
+
+    let mut code = Vec::with_capacity(file.lines.len());
+    for (lnb, line) in file.lines.iter().enumerate() {
+        compiler.lnb = lnb;
+        code.push(Line {
+            instruction: compiler.instruction(&line.instruction)?,
+            span: line.span.clone(),
+        });
+    }
+    Ok((code, Mem::new(compiler.names)))
+}
+
+struct Compiler<'a> {
+    flags: &'a HashMap<String, usize>,
+    slots: HashMap<String, usize>,
+    names: Vec<String>,
+    /// Declared type of each variable, `None` if it is declared with different types
+    types: HashMap<String, Option<Type>>,
+    lnb: usize,
+}
+
+impl Compiler<'_> {
+    fn instruction(&mut self, line: &Instruction) -> Result<Instruction, Error> {
+        match line {
+            Instruction::Set { var, value } => self.check_value(var, value, false)?,
+            Instruction::Add { var, value }
+            | Instruction::Sub { var, value }
+            | Instruction::Mul { var, value }
+            | Instruction::Div { var, value }
+            | Instruction::Mod { var, value }
+            | Instruction::And { var, value }
+            | Instruction::Or { var, value }
+            | Instruction::Xor { var, value }
+            | Instruction::Shl { var, value }
+            | Instruction::Shr { var, value } => self.check_value(var, value, true)?,
+            Instruction::Cadd { op1, op2 }
+            | Instruction::Csub { op1, op2 }
+            | Instruction::Cmul { op1, op2 }
+            | Instruction::Cdiv { op1, op2 }
+            | Instruction::Cmod { op1, op2 }
+            | Instruction::Cand { op1, op2 }
+            | Instruction::Cor { op1, op2 }
+            | Instruction::Cxor { op1, op2 }
+            | Instruction::Cshl { op1, op2 }
+            | Instruction::Cshr { op1, op2 }
+            | Instruction::Add3 { op1, op2, .. }
+            | Instruction::Sub3 { op1, op2, .. }
+            | Instruction::Mul3 { op1, op2, .. }
+            | Instruction::Div3 { op1, op2, .. }
+            | Instruction::Mod3 { op1, op2, .. } => self.check_operands(op1, op2, false)?,
+            Instruction::Jeq { op1, op2, .. }
+            | Instruction::Jneq { op1, op2, .. }
+            | Instruction::Jlt { op1, op2, .. }
+            | Instruction::Jle { op1, op2, .. }
+            | Instruction::Jgt { op1, op2, .. }
+            | Instruction::Jge { op1, op2, .. }
+            | Instruction::Ceq { op1, op2 }
+            | Instruction::Cne { op1, op2 }
+            | Instruction::Clt { op1, op2 }
+            | Instruction::Cle { op1, op2 }
+            | Instruction::Cgt { op1, op2 }
+            | Instruction::Cge { op1, op2 } => self.check_operands(op1, op2, true)?,
+            _ => {
+                if let Some((Val::Const(val), from, _)) = line.conversion() {
+                    if !val.fits(from) {
+                        return Err(Error::could_not_parse(from, val, self.lnb));
+                    }
+                }
+            }
+        }
+        Ok(match line {
+            Instruction::Var { var, var_type } => Instruction::Var {
+                var: self.var(var),
+                var_type: *var_type,
+            },
+            Instruction::Set { var, value } => Instruction::Set {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Add { var, value } => Instruction::Add {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Sub { var, value } => Instruction::Sub {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Mul { var, value } => Instruction::Mul {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Div { var, value } => Instruction::Div {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Mod { var, value } => Instruction::Mod {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Add3 { var, op1, op2 } => Instruction::Add3 {
+                var: self.var(var),
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Sub3 { var, op1, op2 } => Instruction::Sub3 {
+                var: self.var(var),
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Mul3 { var, op1, op2 } => Instruction::Mul3 {
+                var: self.var(var),
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Div3 { var, op1, op2 } => Instruction::Div3 {
+                var: self.var(var),
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Mod3 { var, op1, op2 } => Instruction::Mod3 {
+                var: self.var(var),
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::And { var, value } => Instruction::And {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Or { var, value } => Instruction::Or {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Xor { var, value } => Instruction::Xor {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Shl { var, value } => Instruction::Shl {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Shr { var, value } => Instruction::Shr {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Not { var } => Instruction::Not { var: self.var(var) },
+            Instruction::Itf { var, value } => Instruction::Itf {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Cti { var, value } => Instruction::Cti {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Itc { var, value } => Instruction::Itc {
+                var: self.var(var),
+                value: self.val(value),
+            },
+            Instruction::Fti {
+                var,
+                value,
+                rounding,
+            } => Instruction::Fti {
+                var: self.var(var),
+                value: self.val(value),
+                rounding: *rounding,
+            },
+            Instruction::Del { var } => Instruction::Del { var: self.var(var) },
+            Instruction::Inc { var } => Instruction::Inc { var: self.var(var) },
+            Instruction::Dec { var } => Instruction::Dec { var: self.var(var) },
+            Instruction::Gto { flag } => Instruction::Gto {
+                flag: self.flag(flag)?,
+            },
+            Instruction::Cal { flag } => Instruction::Cal {
+                flag: self.flag(flag)?,
+            },
+            Instruction::Yld { value } => Instruction::Yld {
+                value: self.val(value),
+            },
+            Instruction::Jmp { var, flag } => Instruction::Jmp {
+                var: self.var(var),
+                flag: self.flag(flag)?,
+            },
+            Instruction::Jne { var, flag } => Instruction::Jne {
+                var: self.var(var),
+                flag: self.flag(flag)?,
+            },
+            Instruction::Jeq { op1, op2, flag } => Instruction::Jeq {
+                op1: self.val(op1),
+                op2: self.val(op2),
+                flag: self.flag(flag)?,
+            },
+            Instruction::Jneq { op1, op2, flag } => Instruction::Jneq {
+                op1: self.val(op1),
+                op2: self.val(op2),
+                flag: self.flag(flag)?,
+            },
+            Instruction::Jlt { op1, op2, flag } => Instruction::Jlt {
+                op1: self.val(op1),
+                op2: self.val(op2),
+                flag: self.flag(flag)?,
+            },
+            Instruction::Jle { op1, op2, flag } => Instruction::Jle {
+                op1: self.val(op1),
+                op2: self.val(op2),
+                flag: self.flag(flag)?,
+            },
+            Instruction::Jgt { op1, op2, flag } => Instruction::Jgt {
+                op1: self.val(op1),
+                op2: self.val(op2),
+                flag: self.flag(flag)?,
+            },
+            Instruction::Jge { op1, op2, flag } => Instruction::Jge {
+                op1: self.val(op1),
+                op2: self.val(op2),
+                flag: self.flag(flag)?,
+            },
+            Instruction::Prt { value } => Instruction::Prt {
+                value: self.val(value),
+            },
+            Instruction::Inp { var } => Instruction::Inp { var: self.var(var) },
+            Instruction::Push { value } => Instruction::Push {
+                value: self.val(value),
+            },
+            Instruction::Pop { var } => Instruction::Pop { var: self.var(var) },
+            Instruction::Cadd { op1, op2 } => Instruction::Cadd {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Csub { op1, op2 } => Instruction::Csub {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cmul { op1, op2 } => Instruction::Cmul {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cdiv { op1, op2 } => Instruction::Cdiv {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cmod { op1, op2 } => Instruction::Cmod {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cand { op1, op2 } => Instruction::Cand {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cor { op1, op2 } => Instruction::Cor {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cxor { op1, op2 } => Instruction::Cxor {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cshl { op1, op2 } => Instruction::Cshl {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cshr { op1, op2 } => Instruction::Cshr {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cnot { value } => Instruction::Cnot {
+                value: self.val(value),
+            },
+            Instruction::Ceq { op1, op2 } => Instruction::Ceq {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cne { op1, op2 } => Instruction::Cne {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Clt { op1, op2 } => Instruction::Clt {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cle { op1, op2 } => Instruction::Cle {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cgt { op1, op2 } => Instruction::Cgt {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Cge { op1, op2 } => Instruction::Cge {
+                op1: self.val(op1),
+                op2: self.val(op2),
+            },
+            Instruction::Flg | Instruction::Ret | Instruction::Pas | Instruction::Nll => {
+                line.clone()
+            }
+        })
+    }
+
+    /// Returns the slot of a variable, giving it a new one the first time it is seen
+    fn slot(&mut self, name: &str) -> usize {
+        match self.slots.get(name) {
+            Some(slot) => *slot,
+            None => {
+                let slot = self.names.len();
+                self.slots.insert(name.to_owned(), slot);
+                self.names.push(name.to_owned());
+                slot
+            }
+        }
+    }
+
+    fn var(&mut self, var: &Var) -> Var {
+        Var {
+            name: var.name.clone(),
+            slot: Some(self.slot(&var.name)),
+        }
+    }
+
+    fn flag(&self, flag: &Flag) -> Result<Flag, Error> {
+        match self.flags.get(&flag.name) {
+            Some(line) => Ok(Flag {
+                name: flag.name.clone(),
+                line: Some(*line),
+            }),
+            None => Err(Error::CouldNotFindFlag(flag.name.clone(), self.lnb)),
+        }
+    }
+
+    fn val(&mut self, val: &Val) -> Val {
+        match val {
+            Val::Var(var) => Val::Var(self.var(var)),
+            Val::Const(val) => Val::Const(*val),
+        }
+    }
+
+    /// Returns the declared type of a variable, if it is always declared with the same one
+    fn declared(&self, var: &Var) -> Option<&Type> {
+        self.types.get(&var.name)?.as_ref()
+    }
+
+    /// Checks that a hardcoded value given to `var` fits its declared type.
+    ///
+    /// Arithmetic on `chr` is not checked here, as it fails whatever the value is.
+    fn check_value(&self, var: &Var, value: &Val, arithmetic: bool) -> Result<(), Error> {
+        let val = match value {
+            Val::Const(val) => val,
+            Val::Var(_) => return Ok(()),
+        };
+        match self.declared(var) {
+            Some(Type::Int) if val.as_int().is_none() => {
+                Err(Error::CouldNotParseIntValue(val.to_string(), self.lnb))
+            }
+            Some(Type::Flt) if val.as_flt().is_none() => {
+                Err(Error::CouldNotParseFltValue(val.to_string(), self.lnb))
+            }
+            Some(Type::Chr) if !arithmetic && val.as_chr().is_none() => {
+                Err(Error::CouldNotParseChrValue(val.to_string(), self.lnb))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that a hardcoded operand of a carry operation or a comparison fits the type of the
+    /// other one. Only comparisons accept `chr` operands.
+    fn check_operands(&self, op1: &Val, op2: &Val, comparison: bool) -> Result<(), Error> {
+        let (var, val) = match (op1, op2) {
+            (Val::Var(var), Val::Const(val)) | (Val::Const(val), Val::Var(var)) => (var, val),
+            _ => return Ok(()),
+        };
+        match self.declared(var) {
+            Some(Type::Int) if val.as_int().is_none() => {
+                Err(Error::CannotDetermineReturnType(self.lnb))
+            }
+            Some(Type::Flt) if val.as_flt().is_none() => {
+                Err(Error::CannotDetermineReturnType(self.lnb))
+            }
+            Some(Type::Chr) if comparison && val.as_chr().is_none() => {
+                Err(Error::CannotDetermineReturnType(self.lnb))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use super::instructions::Instruction;
+use super::memory::Cll;
+use super::runtime::{Program, State};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufRead, Write};
+
+/// Interactive debugger driving a `Program` through a line-oriented command protocol.
+///
+/// Lines are 1-based in commands and outputs, like in diagnostics. The commands are :
+///
+/// - `break <line|flag>` : stop before executing the given line, or the given flag
+/// - `delete <line|flag>` : remove a breakpoint
+/// - `watch <var>` : stop after any instruction changing the variable
+/// - `unwatch <var>` : remove a watchpoint
+/// - `step` : execute one instruction
+/// - `next` : execute one instruction, running subroutines called by `cal` entirely
+/// - `continue` : run until a breakpoint, a watchpoint, a pause, an error or the end
+/// - `print <var>` : print a variable
+/// - `memory` : print every variable
+/// - `where` : print the current line
+/// - `quit` : stop reading commands
+pub struct Debugger {
+    program: Program,
+    breakpoints: BTreeSet<usize>,
+    watchpoints: BTreeMap<String, Option<Cll>>,
+}
+
+impl Debugger {
+    /// Constructs a new debugger, stopped before the current line of `program`
+    pub fn new(program: Program) -> Self {
+        Self {
+            program,
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the debugged program
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Returns the debugged program, ending the debugging session
+    pub fn into_program(self) -> Program {
+        self.program
+    }
+
+    /// Reads commands from `input`, one per line, and writes their output into `output`,
+    /// until `quit` or the end of `input`
+    pub fn serve(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for command in input.lines() {
+            let command = command?;
+            if command.trim() == "quit" {
+                break;
+            }
+            write!(output, "{}", self.command(&command))?;
+        }
+        Ok(())
+    }
+
+    /// Executes a single command and returns its output
+    pub fn command(&mut self, command: &str) -> String {
+        let mut words = command.split_whitespace();
+        let (name, arg) = (words.next().unwrap_or(""), words.next());
+        match (name, arg) {
+            ("", _) => String::new(),
+            ("break", Some(target)) | ("b", Some(target)) => match self.target(target) {
+                Some(line) => {
+                    self.breakpoints.insert(line);
+                    format!("breakpoint at line {}\n", line + 1)
+                }
+                None => format!("unknown line or flag `{}`\n", target),
+            },
+            ("delete", Some(target)) | ("d", Some(target)) => match self.target(target) {
+                Some(line) if self.breakpoints.remove(&line) => {
+                    format!("deleted breakpoint at line {}\n", line + 1)
+                }
+                _ => format!("no breakpoint at `{}`\n", target),
+            },
+            ("watch", Some(var)) | ("w", Some(var)) => {
+                let value = self.program.memory.get(var).cloned();
+                self.watchpoints.insert(var.to_owned(), value);
+                format!("watching `{}`\n", var)
+            }
+            ("unwatch", Some(var)) => match self.watchpoints.remove(var) {
+                Some(_) => format!("stopped watching `{}`\n", var),
+                None => format!("`{}` is not watched\n", var),
+            },
+            ("step", None) | ("s", None) => self.step(),
+            ("next", None) | ("n", None) => self.next(),
+            ("continue", None) | ("c", None) => self.resume(0),
+            ("print", Some(var)) | ("p", Some(var)) => match self.program.memory.get(var) {
+                Some(cll) => format!("{} = {}\n", var, cll),
+                None => format!("variable `{}` does not exist\n", var),
+            },
+            ("memory", None) | ("m", None) => {
+                let variables: BTreeMap<_, _> = self.program.memory.iter().collect();
+                variables
+                    .iter()
+                    .map(|(var, cll)| format!("{} = {}\n", var, cll))
+                    .collect()
+            }
+            ("where", None) => self.location(),
+            _ => format!("unknown command `{}`\n", command.trim()),
+        }
+    }
+
+    /// Executes one instruction
+    fn step(&mut self) -> String {
+        match self.advance() {
+            Some(stop) => stop,
+            None => self.location(),
+        }
+    }
+
+    /// Executes one instruction, or a whole subroutine if it is a `cal`
+    fn next(&mut self) -> String {
+        let code = self.program.code();
+        match code.get(self.program.lnb).map(|line| &line.instruction) {
+            Some(Instruction::Cal { .. }) => {
+                let depth = self.program.call_stack().len();
+                if let Some(stop) = self.advance() {
+                    return stop;
+                }
+                self.resume(depth + 1)
+            }
+            _ => self.step(),
+        }
+    }
+
+    /// Runs the program until it stops, or until its call stack is no deeper than `depth`
+    fn resume(&mut self, depth: usize) -> String {
+        if let Some(stop) = self.advance() {
+            return stop;
+        }
+        loop {
+            if depth > 0 && self.program.call_stack().len() < depth {
+                return self.location();
+            }
+            if self.breakpoints.contains(&self.program.lnb) {
+                return format!("breakpoint, {}", self.location());
+            }
+            if let Some(stop) = self.advance() {
+                return stop;
+            }
+        }
+    }
+
+    /// Executes one instruction, returns why the program should stop if it should
+    fn advance(&mut self) -> Option<String> {
+        match self.program.step() {
+            Err(e) => Some(self.program.render(&e)),
+            Ok(step) => {
+                // The last instruction may have changed a watched value before the program stopped
+                let changes = self.check_watchpoints(step.line);
+                let stop = match step.state {
+                    Some(State::Finished(line)) => {
+                        format!("program finished at line {}\n", line + 1)
+                    }
+                    Some(State::Paused(line)) => {
+                        format!("program paused at line {}, {}", line + 1, self.location())
+                    }
+                    Some(State::Yielded(value)) => {
+                        format!("program yielded {}, {}", value.plain(), self.location())
+                    }
+                    None if changes.is_empty() => return None,
+                    None => self.location(),
+                };
+                Some(changes + &stop)
+            }
+        }
+    }
+
+    /// Updates watched values, returns the changes
+    fn check_watchpoints(&mut self, line: usize) -> String {
+        let mut changes = String::new();
+        for (var, watched) in self.watchpoints.iter_mut() {
+            let value = self.program.memory.get(var).cloned();
+            if value != *watched {
+                changes += &format!(
+                    "watchpoint `{}` at line {} : {} -> {}\n",
+                    var,
+                    line + 1,
+                    describe(watched),
+                    describe(&value)
+                );
+                *watched = value;
+            }
+        }
+        changes
+    }
+
+    /// Returns the current line and its source
+    fn location(&self) -> String {
+        let line = self.program.lnb;
+        match self.program.file().source_line(line) {
+            Some(text) => format!("line {} : {}\n", line + 1, text.trim()),
+            None => format!("line {} : end of program\n", line + 1),
+        }
+    }
+
+    /// Returns the 0-based line of a 1-based line number or a flag name
+    fn target(&self, target: &str) -> Option<usize> {
+        match target.parse::<usize>() {
+            Ok(line) if line > 0 => Some(line - 1),
+            Ok(_) => None,
+            Err(_) => self.program.file().flags.get(target).copied(),
+        }
+    }
+}
+
+fn describe(value: &Option<Cll>) -> String {
+    match value {
+        Some(cll) => cll.to_string(),
+        None => "undefined".to_owned(),
+    }
+}
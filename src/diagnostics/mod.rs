@@ -0,0 +1,143 @@
+use super::instructions::Line;
+use super::lexer;
+use super::ProgramFile;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// Location of an instruction in the source of a program.
+///
+/// Columns are 0-based and counted in characters, `line` is the 0-based line number.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Span {
+    /// Name of the source file, if the program was read from one
+    pub file: Option<Rc<str>>,
+    /// Line of the instruction
+    pub line: usize,
+    /// Columns covered by the whole instruction
+    pub columns: Range<usize>,
+    /// Columns covered by the instruction name, before `:`
+    pub instruction: Range<usize>,
+    /// Columns covered by each operand, after `:`
+    pub operands: Vec<Range<usize>>,
+}
+
+impl Span {
+    /// Computes the span of the given source line.
+    pub fn of_line(file: Option<Rc<str>>, line: usize, text: &str) -> Self {
+        let (instruction, operands) = match lexer::tokenize(text) {
+            Some(tokens) => (
+                tokens.head.columns,
+                tokens.operands.into_iter().map(|op| op.columns).collect(),
+            ),
+            None => (trimmed_columns(text), Vec::new()),
+        };
+        let columns = match operands.last() {
+            Some(last) if last.end > instruction.start => instruction.start..last.end,
+            _ => instruction.clone(),
+        };
+        Self {
+            file,
+            line,
+            columns,
+            instruction,
+            operands,
+        }
+    }
+}
+
+/// Returns the character columns of `text`, without surrounding whitespaces.
+fn trimmed_columns(text: &str) -> Range<usize> {
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len().max(start);
+    text[..start].chars().count()..text[..end].chars().count()
+}
+
+/// Part of a line pointed at by a diagnostic
+#[derive(Debug, PartialEq, Clone)]
+pub enum Highlight {
+    /// The whole instruction
+    Line,
+    /// The instruction name
+    Instruction,
+    /// The operand at the given 0-based index
+    Operand(usize),
+    /// The first operand written as the given text, e.g. a variable name
+    Text(String),
+}
+
+/// Errors that can be tied to a location in a program
+pub trait Located: fmt::Display {
+    /// Returns how severe the error is, `error` or `warning`.
+    fn level(&self) -> &'static str {
+        "error"
+    }
+    /// Returns the stable code of the error, e.g. `E0010`.
+    fn code(&self) -> &'static str;
+    /// Returns the error message, without its location.
+    fn message(&self) -> String;
+    /// Returns the 0-based line of the error and the part of it to point at, if any.
+    fn location(&self) -> Option<(usize, Highlight)>;
+}
+
+/// Renders the given error as a rustc-style diagnostic, using the spans of the given lines of
+/// the file :
+///
+/// ```text
+/// error[E0102]: variable `&b` does not exist
+///  --> program.moon:4:10
+///   |
+/// 4 | add: &a, &b
+///   |          ^^
+/// ```
+pub fn render(file: &ProgramFile, lines: &[Line], error: &impl Located) -> String {
+    let mut out = format!("{}[{}]: {}\n", error.level(), error.code(), error.message());
+    let (line, highlight) = match error.location() {
+        Some(location) => location,
+        None => return out,
+    };
+    let text = file.source_line(line).unwrap_or("");
+    let span = lines
+        .get(line)
+        .map(|line| line.span.clone())
+        .unwrap_or_else(|| Span::of_line(file.name.clone(), line, text));
+    let columns = match highlight {
+        Highlight::Line => span.columns.clone(),
+        Highlight::Instruction => span.instruction.clone(),
+        Highlight::Operand(n) => span.operands.get(n).unwrap_or(&span.columns).clone(),
+        Highlight::Text(name) => span
+            .operands
+            .iter()
+            .find(|op| {
+                let operand: String = text.chars().skip(op.start).take(op.len()).collect();
+                operand == name
+            })
+            .unwrap_or(&span.columns)
+            .clone(),
+    };
+
+    let number = (line + 1).to_string();
+    let gutter = " ".repeat(number.len());
+    out += &format!(
+        "{}--> {}:{}:{}\n",
+        gutter,
+        span.file.as_deref().unwrap_or("<source>"),
+        number,
+        columns.start + 1
+    );
+    out += &format!("{} |\n", gutter);
+    out += &format!("{} | {}\n", number, text);
+    // Tabs are kept in the padding, so that the carets are aligned whatever their width
+    let padding: String = text
+        .chars()
+        .take(columns.start)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    out += &format!(
+        "{} | {}{}\n",
+        gutter,
+        padding,
+        "^".repeat(columns.len().max(1))
+    );
+    out
+}
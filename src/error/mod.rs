@@ -0,0 +1,72 @@
+use super::diagnostics::{Highlight, Located};
+use super::{Error, ErrorIO, Program, ProgramFile, State};
+use std::fmt;
+
+/// Any error that can happen while loading or running a program
+#[derive(Debug)]
+pub enum MoonError {
+    /// The program could not be read or parsed
+    Parse(ErrorIO),
+    /// The program failed during its execution
+    Runtime(Error),
+}
+
+impl From<ErrorIO> for MoonError {
+    fn from(e: ErrorIO) -> Self {
+        MoonError::Parse(e)
+    }
+}
+
+impl From<Error> for MoonError {
+    fn from(e: Error) -> Self {
+        MoonError::Runtime(e)
+    }
+}
+
+impl Located for MoonError {
+    fn code(&self) -> &'static str {
+        match self {
+            MoonError::Parse(e) => e.code(),
+            MoonError::Runtime(e) => e.code(),
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            MoonError::Parse(e) => e.message(),
+            MoonError::Runtime(e) => e.message(),
+        }
+    }
+
+    fn location(&self) -> Option<(usize, Highlight)> {
+        match self {
+            MoonError::Parse(e) => e.location(),
+            MoonError::Runtime(e) => e.location(),
+        }
+    }
+}
+
+impl fmt::Display for MoonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoonError::Parse(e) => write!(f, "parse error : {}", e),
+            MoonError::Runtime(e) => write!(f, "runtime error : {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MoonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MoonError::Parse(e) => Some(e),
+            MoonError::Runtime(e) => Some(e),
+        }
+    }
+}
+
+/// Parses and runs the given program source, until it finishes, pauses or yields a value.
+pub fn load_and_run(source: &str) -> Result<State, MoonError> {
+    let mut file = ProgramFile::from_source(source);
+    file.parse()?;
+    Ok(Program::compile(file)?.run()?)
+}
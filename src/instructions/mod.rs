@@ -1,3 +1,7 @@
+use super::diagnostics::Span;
+use super::memory::Cll;
+use std::fmt;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Instruction {
     /// Create an empty variable
@@ -56,6 +60,159 @@ pub enum Instruction {
     /// mod: hello, 3
     /// ```
     Mod { var: Var, value: Val },
+    /// Add op1 and op2 into variable
+    /// Three operands alternative to `add`, leaving op1 and the carry unchanged
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// add: hello, world, 3
+    /// ```
+    Add3 { var: Var, op1: Val, op2: Val },
+    /// Substract op2 from op1 into variable
+    /// Three operands alternative to `sub`, leaving op1 and the carry unchanged
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// sub: hello, world, 5
+    /// ```
+    Sub3 { var: Var, op1: Val, op2: Val },
+    /// Multiply op1 with op2 into variable
+    /// Three operands alternative to `mul`, leaving op1 and the carry unchanged
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// mul: hello, world, 2
+    /// ```
+    Mul3 { var: Var, op1: Val, op2: Val },
+    /// Divides op1 by op2 into variable
+    /// Three operands alternative to `div`, leaving op1 and the carry unchanged
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// div: hello, world, 3
+    /// ```
+    Div3 { var: Var, op1: Val, op2: Val },
+    /// Gives the rest of the division of op1 by op2 into variable
+    /// Three operands alternative to `mod`, leaving op1 and the carry unchanged
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// mod: hello, world, 3
+    /// ```
+    Mod3 { var: Var, op1: Val, op2: Val },
+    /// Bitwise and of variable with value
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// and: hello, 12
+    /// ```
+    And { var: Var, value: Val },
+    /// Bitwise or of variable with value
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// or: hello, 3
+    /// ```
+    Or { var: Var, value: Val },
+    /// Bitwise exclusive or of variable with value
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// xor: hello, 255
+    /// ```
+    Xor { var: Var, value: Val },
+    /// Shift the bits of variable to the left by value
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// shl: hello, 2
+    /// ```
+    Shl { var: Var, value: Val },
+    /// Shift the bits of variable to the right by value, keeping its sign
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// shr: hello, 2
+    /// ```
+    Shr { var: Var, value: Val },
+    /// Invert the bits of variable
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// not: hello
+    /// ```
+    Not { var: Var },
+    /// Convert an `int` value into a `flt` variable
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// itf: hello, world
+    /// ```
+    Itf { var: Var, value: Val },
+    /// Convert a `flt` value into an `int` variable, truncating it by default or rounding it
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// fti: hello, world, round
+    /// ```
+    Fti {
+        var: Var,
+        value: Val,
+        rounding: Rounding,
+    },
+    /// Convert a `chr` value into an `int` variable, as its code point
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cti: hello, world
+    /// ```
+    Cti { var: Var, value: Val },
+    /// Convert an `int` value, as a code point, into a `chr` variable
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// itc: hello, world
+    /// ```
+    Itc { var: Var, value: Val },
+    /// Delete variable
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// del: hello
+    /// ```
+    Del { var: Var },
+    /// Add one to variable
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// inc: hello
+    /// ```
+    Inc { var: Var },
+    /// Substract one from variable
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// dec: hello
+    /// ```
+    Dec { var: Var },
     /// Create a flag to jump to
     ///
     /// Utilisation :
@@ -72,6 +229,38 @@ pub enum Instruction {
     /// gto: flag
     /// ```
     Gto { flag: Flag },
+    /// Call the subroutine starting at flag : jump to it, and come back on `ret`
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cal: flag
+    /// ```
+    Cal { flag: Flag },
+    /// Return from the current subroutine, to the line following its `cal`
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// ret:
+    /// ```
+    Ret,
+    /// Pause the execution, until the host resumes it
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// pas:
+    /// ```
+    Pas,
+    /// Pause the execution and hand value to the host, until it resumes it
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// yld: hello
+    /// ```
+    Yld { value: Val },
     /// Jump to flag if result is == 0
     ///
     /// Utilisation :
@@ -88,6 +277,55 @@ pub enum Instruction {
     /// jne: hello, flag
     /// ```
     Jne { var: Var, flag: Flag },
+    /// Jump to flag if op1 == op2
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// jeq: hello, 3, flag
+    /// ```
+    Jeq { op1: Val, op2: Val, flag: Flag },
+    /// Jump to flag if op1 != op2
+    /// Three operands alternative to `jne`
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// jne: hello, 3, flag
+    /// ```
+    Jneq { op1: Val, op2: Val, flag: Flag },
+    /// Jump to flag if op1 < op2
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// jlt: hello, 3, flag
+    /// ```
+    Jlt { op1: Val, op2: Val, flag: Flag },
+    /// Jump to flag if op1 <= op2
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// jle: hello, 3, flag
+    /// ```
+    Jle { op1: Val, op2: Val, flag: Flag },
+    /// Jump to flag if op1 > op2
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// jgt: hello, 3, flag
+    /// ```
+    Jgt { op1: Val, op2: Val, flag: Flag },
+    /// Jump to flag if op1 >= op2
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// jge: hello, 3, flag
+    /// ```
+    Jge { op1: Val, op2: Val, flag: Flag },
     /// Do nothing and is ignored
     ///
     /// Utilisation :
@@ -104,6 +342,30 @@ pub enum Instruction {
     /// prt: hello
     /// ```
     Prt { value: Val },
+    /// Read a line from input and store it into variable, parsed according to its type
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// inp: hello
+    /// ```
+    Inp { var: Var },
+    /// Push value on top of the stack
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// push: hello
+    /// ```
+    Push { value: Val },
+    /// Pop the value on top of the stack into variable, which must have the same type
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// pop: hello
+    /// ```
+    Pop { var: Var },
 
     // carry operations :
     /// Add value to variable and store the result into `-`
@@ -151,24 +413,360 @@ pub enum Instruction {
     /// cmod: hello, 3
     /// ```
     Cmod { op1: Val, op2: Val },
+    /// Bitwise and of op1 with op2 and store the result into `-`
+    /// Carry alternative to `and`
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cand: hello, 12
+    /// ```
+    Cand { op1: Val, op2: Val },
+    /// Bitwise or of op1 with op2 and store the result into `-`
+    /// Carry alternative to `or`
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cor: hello, 3
+    /// ```
+    Cor { op1: Val, op2: Val },
+    /// Bitwise exclusive or of op1 with op2 and store the result into `-`
+    /// Carry alternative to `xor`
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cxor: hello, 255
+    /// ```
+    Cxor { op1: Val, op2: Val },
+    /// Shift the bits of op1 to the left by op2 and store the result into `-`
+    /// Carry alternative to `shl`
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cshl: hello, 2
+    /// ```
+    Cshl { op1: Val, op2: Val },
+    /// Shift the bits of op1 to the right by op2, keeping its sign and store the result into `-`
+    /// Carry alternative to `shr`
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cshr: hello, 2
+    /// ```
+    Cshr { op1: Val, op2: Val },
+    /// Invert the bits of value and store the result into `-`
+    /// Carry alternative to `not`
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cnot: hello
+    /// ```
+    Cnot { value: Val },
+    /// Store 1 into `-` if op1 == op2, 0 otherwise
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// ceq: hello, 3
+    /// ```
+    Ceq { op1: Val, op2: Val },
+    /// Store 1 into `-` if op1 != op2, 0 otherwise
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cne: hello, 3
+    /// ```
+    Cne { op1: Val, op2: Val },
+    /// Store 1 into `-` if op1 < op2, 0 otherwise
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// clt: hello, 3
+    /// ```
+    Clt { op1: Val, op2: Val },
+    /// Store 1 into `-` if op1 <= op2, 0 otherwise
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cle: hello, 3
+    /// ```
+    Cle { op1: Val, op2: Val },
+    /// Store 1 into `-` if op1 > op2, 0 otherwise
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cgt: hello, 3
+    /// ```
+    Cgt { op1: Val, op2: Val },
+    /// Store 1 into `-` if op1 >= op2, 0 otherwise
+    ///
+    /// Utilisation :
+    ///
+    /// ```Moon
+    /// cge: hello, 3
+    /// ```
+    Cge { op1: Val, op2: Val },
+}
+
+impl Instruction {
+    /// Returns the variable written, created or deleted by the instruction, if any
+    pub fn written_variable(&self) -> Option<&str> {
+        match self {
+            Instruction::Var { var, .. }
+            | Instruction::Set { var, .. }
+            | Instruction::Add { var, .. }
+            | Instruction::Sub { var, .. }
+            | Instruction::Mul { var, .. }
+            | Instruction::Div { var, .. }
+            | Instruction::Mod { var, .. }
+            | Instruction::Add3 { var, .. }
+            | Instruction::Sub3 { var, .. }
+            | Instruction::Mul3 { var, .. }
+            | Instruction::Div3 { var, .. }
+            | Instruction::Mod3 { var, .. }
+            | Instruction::And { var, .. }
+            | Instruction::Or { var, .. }
+            | Instruction::Xor { var, .. }
+            | Instruction::Shl { var, .. }
+            | Instruction::Shr { var, .. }
+            | Instruction::Not { var }
+            | Instruction::Itf { var, .. }
+            | Instruction::Fti { var, .. }
+            | Instruction::Cti { var, .. }
+            | Instruction::Itc { var, .. }
+            | Instruction::Del { var }
+            | Instruction::Inc { var }
+            | Instruction::Dec { var }
+            | Instruction::Inp { var }
+            | Instruction::Pop { var } => Some(&var.name),
+            Instruction::Cadd { .. }
+            | Instruction::Csub { .. }
+            | Instruction::Cmul { .. }
+            | Instruction::Cdiv { .. }
+            | Instruction::Cmod { .. }
+            | Instruction::Cand { .. }
+            | Instruction::Cor { .. }
+            | Instruction::Cxor { .. }
+            | Instruction::Cshl { .. }
+            | Instruction::Cshr { .. }
+            | Instruction::Cnot { .. }
+            | Instruction::Ceq { .. }
+            | Instruction::Cne { .. }
+            | Instruction::Clt { .. }
+            | Instruction::Cle { .. }
+            | Instruction::Cgt { .. }
+            | Instruction::Cge { .. } => Some(crate::CARRY_VARIABLE),
+            _ => None,
+        }
+    }
+
+    /// Returns the value converted by a conversion instruction, with the types it converts from
+    /// and to, if it is one
+    pub fn conversion(&self) -> Option<(&Val, Type, Type)> {
+        match self {
+            Instruction::Itf { value, .. } => Some((value, Type::Int, Type::Flt)),
+            Instruction::Fti { value, .. } => Some((value, Type::Flt, Type::Int)),
+            Instruction::Cti { value, .. } => Some((value, Type::Chr, Type::Int)),
+            Instruction::Itc { value, .. } => Some((value, Type::Int, Type::Chr)),
+            _ => None,
+        }
+    }
+
+    /// Returns the flag the instruction jumps to, if any
+    pub fn flag(&self) -> Option<&Flag> {
+        match self {
+            Instruction::Gto { flag }
+            | Instruction::Cal { flag }
+            | Instruction::Jmp { flag, .. }
+            | Instruction::Jne { flag, .. }
+            | Instruction::Jeq { flag, .. }
+            | Instruction::Jneq { flag, .. }
+            | Instruction::Jlt { flag, .. }
+            | Instruction::Jle { flag, .. }
+            | Instruction::Jgt { flag, .. }
+            | Instruction::Jge { flag, .. } => Some(flag),
+            _ => None,
+        }
+    }
+}
+
+/// A line of a program : its instruction, and where it is written in the source
+#[derive(Debug, PartialEq, Clone)]
+pub struct Line {
+    pub instruction: Instruction,
+    pub span: Span,
+}
+
+/// Represents a variable, resolved to its memory slot when the program is compiled
+#[derive(Debug, PartialEq, Clone)]
+pub struct Var {
+    pub name: String,
+    /// Memory slot of the variable, `None` until the program is compiled
+    pub slot: Option<usize>,
+}
+
+impl Var {
+    /// Returns a new variable, not resolved yet
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            slot: None,
+        }
+    }
+
+    /// Returns the memory slot of the variable, only called on compiled code
+    pub(crate) fn slot(&self) -> usize {
+        match self.slot {
+            Some(slot) => slot,
+            None => panic!("variable `{}` is not resolved to a slot", self.name),
+        }
+    }
+}
+
+impl fmt::Display for Var {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Represents a flag, resolved to the line it is created at when the program is compiled
+#[derive(Debug, PartialEq, Clone)]
+pub struct Flag {
+    pub name: String,
+    /// Line of the flag, `None` until the program is compiled
+    pub line: Option<usize>,
 }
 
-/// Represents a variable
-type Var = String;
-/// Represents a flag
-type Flag = String;
+impl Flag {
+    /// Returns a new flag, not resolved yet
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            line: None,
+        }
+    }
+
+    /// Returns the line of the flag, only called on compiled code
+    pub(crate) fn line(&self) -> usize {
+        match self.line {
+            Some(line) => line,
+            None => panic!("flag `{}` is not resolved to a line", self.name),
+        }
+    }
+}
+
+impl fmt::Display for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
 
 /// Gives either a hardcoded value or a variable-stored one
 #[derive(Debug, PartialEq, Clone)]
 pub enum Val {
     /// Links to a variable
     Var(Var),
-    /// Gives directly a value
-    Value(String),
+    /// Gives directly a value, typed when the program is parsed
+    Const(Const),
+}
+
+/// A hardcoded value, typed when the program is parsed
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Const {
+    Int(i32),
+    Flt(f64),
+    Chr(char),
+}
+
+impl Const {
+    /// Infers the type of a hardcoded value : `int`, then `flt`, then `chr`
+    pub fn parse(text: &str) -> Option<Self> {
+        if let Ok(int) = text.parse() {
+            Some(Const::Int(int))
+        } else if let Ok(flt) = text.parse() {
+            Some(Const::Flt(flt))
+        } else if let Ok(chr) = text.parse() {
+            Some(Const::Chr(chr))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value as an `int`, if it is one
+    pub fn as_int(self) -> Option<i32> {
+        match self {
+            Const::Int(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `flt` : an `int` value can be used as a `flt`
+    pub fn as_flt(self) -> Option<f64> {
+        match self {
+            Const::Int(val) => Some(f64::from(val)),
+            Const::Flt(val) => Some(val),
+            Const::Chr(_) => None,
+        }
+    }
+
+    /// Returns the value as a `chr` : a single digit is both an `int` and a `chr`
+    pub fn as_chr(self) -> Option<char> {
+        match self {
+            Const::Int(val @ 0..=9) => std::char::from_digit(val as u32, 10),
+            Const::Chr(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Returns the type of the value
+    pub fn type_of(self) -> Type {
+        match self {
+            Const::Int(_) => Type::Int,
+            Const::Flt(_) => Type::Flt,
+            Const::Chr(_) => Type::Chr,
+        }
+    }
+
+    /// Returns `true` if the value can be given to a variable of the given type
+    pub fn fits(self, var_type: Type) -> bool {
+        match var_type {
+            Type::Int => self.as_int().is_some(),
+            Type::Flt => self.as_flt().is_some(),
+            Type::Chr => self.as_chr().is_some(),
+        }
+    }
+
+    /// Returns a memory cell holding the value
+    pub fn to_cll(self) -> Cll {
+        match self {
+            Const::Int(val) => Cll::Int(Some(val)),
+            Const::Flt(val) => Cll::Flt(Some(val)),
+            Const::Chr(val) => Cll::Chr(Some(val)),
+        }
+    }
+}
+
+impl fmt::Display for Const {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Const::Int(val) => write!(f, "{}", val),
+            Const::Flt(val) => write!(f, "{}", val),
+            Const::Chr(val) => write!(f, "{}", val),
+        }
+    }
 }
 
 /// Defines a type : `int`, `flt` or `chr`
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Type {
     /// Integer type
     Int,
@@ -177,3 +775,125 @@ pub enum Type {
     /// Char type
     Chr,
 }
+
+/// Defines how `fti` converts a `flt` into an `int` : `trunc` or `round`
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Rounding {
+    /// Towards zero, the default one
+    #[default]
+    Trunc,
+    /// To the nearest `int`, away from zero on halves
+    Round,
+}
+
+impl Rounding {
+    /// Applies the rounding on a `flt`
+    pub fn apply(self, val: f64) -> f64 {
+        match self {
+            Rounding::Trunc => val.trunc(),
+            Rounding::Round => val.round(),
+        }
+    }
+}
+
+/// Number of operands an instruction can be written with
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Arity {
+    pub min: usize,
+    pub max: usize,
+}
+
+/// Arity of each instruction, by name
+const ARITIES: &[(&str, Arity)] = &[
+    ("var", Arity::exactly(2)),
+    ("set", Arity::exactly(2)),
+    ("add", Arity::between(2, 3)),
+    ("sub", Arity::between(2, 3)),
+    ("mul", Arity::between(2, 3)),
+    ("div", Arity::between(2, 3)),
+    ("mod", Arity::between(2, 3)),
+    ("and", Arity::exactly(2)),
+    ("or", Arity::exactly(2)),
+    ("xor", Arity::exactly(2)),
+    ("shl", Arity::exactly(2)),
+    ("shr", Arity::exactly(2)),
+    ("not", Arity::exactly(1)),
+    ("itf", Arity::exactly(2)),
+    ("fti", Arity::between(2, 3)),
+    ("cti", Arity::exactly(2)),
+    ("itc", Arity::exactly(2)),
+    ("inc", Arity::exactly(1)),
+    ("dec", Arity::exactly(1)),
+    ("del", Arity::exactly(1)),
+    ("cadd", Arity::exactly(2)),
+    ("csub", Arity::exactly(2)),
+    ("cmul", Arity::exactly(2)),
+    ("cdiv", Arity::exactly(2)),
+    ("cmod", Arity::exactly(2)),
+    ("cand", Arity::exactly(2)),
+    ("cor", Arity::exactly(2)),
+    ("cxor", Arity::exactly(2)),
+    ("cshl", Arity::exactly(2)),
+    ("cshr", Arity::exactly(2)),
+    ("cnot", Arity::exactly(1)),
+    ("ceq", Arity::exactly(2)),
+    ("cne", Arity::exactly(2)),
+    ("clt", Arity::exactly(2)),
+    ("cle", Arity::exactly(2)),
+    ("cgt", Arity::exactly(2)),
+    ("cge", Arity::exactly(2)),
+    ("gto", Arity::exactly(1)),
+    ("cal", Arity::exactly(1)),
+    ("ret", Arity::exactly(0)),
+    ("jmp", Arity::exactly(2)),
+    ("jne", Arity::between(2, 3)),
+    ("jeq", Arity::exactly(3)),
+    ("jlt", Arity::exactly(3)),
+    ("jle", Arity::exactly(3)),
+    ("jgt", Arity::exactly(3)),
+    ("jge", Arity::exactly(3)),
+    ("flg", Arity::exactly(1)),
+    ("nll", Arity::exactly(1)),
+    ("prt", Arity::exactly(1)),
+    ("pas", Arity::exactly(0)),
+    ("yld", Arity::exactly(1)),
+    ("inp", Arity::exactly(1)),
+    ("push", Arity::exactly(1)),
+    ("pop", Arity::exactly(1)),
+];
+
+impl Arity {
+    pub const fn exactly(n: usize) -> Self {
+        Self { min: n, max: n }
+    }
+
+    pub const fn between(min: usize, max: usize) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the arity of the instruction with the given name, `None` if there is no such instruction
+    pub fn of(instruction: &str) -> Option<Self> {
+        ARITIES
+            .iter()
+            .find(|(name, _)| *name == instruction)
+            .map(|(_, arity)| *arity)
+    }
+
+    /// Returns `true` if an instruction of this arity can be written with `n` operands
+    pub fn accepts(self, n: usize) -> bool {
+        (self.min..=self.max).contains(&n)
+    }
+}
+
+impl fmt::Display for Arity {
+    /// Writes the expected number of operands, e.g. `1 operand` or `2 or 3 operands`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.max == 1 { "" } else { "s" };
+        match (self.min, self.max) {
+            (0, 0) => write!(f, "no operand"),
+            (min, max) if min == max => write!(f, "{} operand{}", max, plural),
+            (min, max) if min + 1 == max => write!(f, "{} or {} operands", min, max),
+            (min, max) => write!(f, "{} to {} operands", min, max),
+        }
+    }
+}
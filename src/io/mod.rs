@@ -1,16 +1,26 @@
-use super::instructions::{Instruction, Type, Val};
+use super::diagnostics::{self, Highlight, Located, Span};
+use super::instructions::{Arity, Const, Flag, Instruction, Line, Rounding, Type, Val, Var};
+use super::lexer;
 use std::collections::HashMap;
+use std::fmt;
 use std::fs::File;
 use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 /// A struct that contains the program, both under its text form and parsed form.
 #[derive(Default, Debug)]
 // TODO use only `&str` instead of `String` during parsing
 pub struct ProgramFile {
     text: String,
-    pub lines: Vec<Instruction>,
+    /// Name of the file the program was read from, if any
+    pub name: Option<Rc<str>>,
+    /// Parsed lines, each with its location in the source
+    pub lines: Vec<Line>,
     line_number: usize,
     pub flags: HashMap<String, usize>,
+    /// Warnings found while parsing the program, e.g. unused flags
+    pub warnings: Vec<Warning>,
 }
 
 impl ProgramFile {
@@ -18,64 +28,205 @@ impl ProgramFile {
     pub fn new() -> Self {
         Self {
             text: String::new(),
+            name: None,
             lines: Vec::new(),
             line_number: 0,
             flags: HashMap::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Returns a new ProgramFile object holding the given source text.
+    pub fn from_source(source: &str) -> Self {
+        Self {
+            text: source.to_owned(),
+            ..Self::new()
+        }
+    }
+
+    /// Reads a program from any `Read` source, e.g. `stdin` or a socket.
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, ErrorIO> {
+        let mut bytes = Vec::new();
+        if let Err(e) = reader.read_to_end(&mut bytes) {
+            return Err(ErrorIO::CannotReadSource(e));
+        }
+        match String::from_utf8(bytes) {
+            Ok(text) => Ok(Self {
+                text,
+                ..Self::new()
+            }),
+            Err(e) => Err(ErrorIO::InvalidUtf8(e.utf8_error().valid_up_to())),
         }
     }
 
     /// Reads a given program file.
-    pub fn open(&mut self, file_name: &'static str) -> Result<(), ErrorIO> {
-        match File::open(file_name) {
-            Ok(mut f) => {
-                f.read_to_string(&mut self.text).unwrap();
-                Ok(())
-            }
-            Err(_) => Err(ErrorIO::CannotReadFile(file_name)),
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ErrorIO> {
+        match File::open(path.as_ref()) {
+            Ok(f) => Ok(Self {
+                name: Some(path.as_ref().to_string_lossy().into()),
+                ..Self::from_reader(f)?
+            }),
+            Err(e) => Err(ErrorIO::CannotReadFile(path.as_ref().to_path_buf(), e)),
         }
     }
 
+    /// Returns the given 0-based line of the source text.
+    pub fn source_line(&self, line: usize) -> Option<&str> {
+        self.text.lines().nth(line)
+    }
+
+    /// Renders the given parse or runtime error as a diagnostic pointing into this file.
+    pub fn render(&self, error: &impl Located) -> String {
+        diagnostics::render(self, &self.lines, error)
+    }
+
     /// Parse the program.
     pub fn parse(&mut self) -> Result<(), ErrorIO> {
-        let program: Vec<&str> = self.text.lines().collect();
-        for (line_number, line) in program.iter().enumerate() {
+        match self.parse_lines(true).into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse the program without stopping at the first error.
+    ///
+    /// Returns every error found in the file : lines that could not be parsed are replaced by
+    /// `Nll`, so that `lines` keeps its numbering and holds everything that could be parsed.
+    pub fn parse_all(&mut self) -> Result<(), Vec<ErrorIO>> {
+        let errors = self.parse_lines(false);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Lists the flags of the program, with the lines they are created at and the lines jumping
+    /// to them, sorted by definition.
+    pub fn flag_sites(&self) -> Vec<FlagSites> {
+        let mut sites: Vec<FlagSites> = self
+            .flags
+            .iter()
+            .map(|(name, line)| FlagSites {
+                name: name.clone(),
+                definition: *line,
+                references: Vec::new(),
+            })
+            .collect();
+        sites.sort_by_key(|site| site.definition);
+        for (line, ins) in self.lines.iter().enumerate() {
+            if let Some(flag) = ins.instruction.flag() {
+                if let Some(site) = sites.iter_mut().find(|site| site.name == flag.name) {
+                    site.references.push(line);
+                }
+            }
+        }
+        sites
+    }
+
+    fn parse_lines(&mut self, stop_at_first_error: bool) -> Vec<ErrorIO> {
+        let mut errors = Vec::new();
+        self.lines.clear();
+        self.flags.clear();
+        self.warnings.clear();
+        let text = std::mem::take(&mut self.text);
+        for (line_number, line) in text.lines().enumerate() {
             self.line_number = line_number;
+            let span = Span::of_line(self.name.clone(), line_number, line);
             match self.parse_line(line) {
-                Ok((ins, Some((flag_name, flag_id)))) => {
-                    self.lines.push(ins);
-                    self.flags.insert(flag_name, flag_id);
+                Ok((instruction, Some((flag_name, flag_id)))) => {
+                    match self.flags.get(&flag_name) {
+                        Some(first) => {
+                            errors.push(ErrorIO::DuplicateFlag(flag_name, *first, flag_id));
+                            // Like other errors, the line is left out or replaced by `Nll`
+                            if stop_at_first_error {
+                                break;
+                            }
+                            self.lines.push(Line {
+                                instruction: Instruction::Nll,
+                                span,
+                            });
+                        }
+                        None => {
+                            self.flags.insert(flag_name, flag_id);
+                            self.lines.push(Line { instruction, span });
+                        }
+                    }
                 }
-                Ok((ins, None)) => {
-                    self.lines.push(ins);
+                Ok((instruction, None)) => {
+                    self.lines.push(Line { instruction, span });
+                }
+                Err(e) => {
+                    errors.push(e);
+                    if stop_at_first_error {
+                        break;
+                    }
+                    self.lines.push(Line {
+                        instruction: Instruction::Nll,
+                        span,
+                    });
+                }
+            }
+        }
+        self.text = text;
+
+        // Jump targets and unused flags are only known once every line is parsed
+        if errors.is_empty() || !stop_at_first_error {
+            for (line, ins) in self.lines.iter().enumerate() {
+                if let Some(flag) = ins.instruction.flag() {
+                    if !self.flags.contains_key(&flag.name) {
+                        errors.push(ErrorIO::UndefinedFlag(flag.name.clone(), line));
+                    }
                 }
-                Err(e) => return Err(e),
             }
+            errors.sort_by_key(|e| e.line());
+            self.warnings = self
+                .flag_sites()
+                .into_iter()
+                .filter(|site| site.references.is_empty())
+                .map(|site| Warning::UnusedFlag(site.name, site.definition))
+                .collect();
         }
-        Ok(())
+        errors
     }
 
     fn parse_line(&self, line: &str) -> Result<(Instruction, Option<(String, usize)>), ErrorIO> {
-        // Remove whitespaces
-        let line: String = line.split_whitespace().collect();
-        // Split instruction / operands
-        let splitted: Vec<&str> = line.split(':').collect();
-        match &splitted.len() {
+        // Split instruction / operands, keeping whitespaces inside them
+        let tokens = match lexer::tokenize(line) {
+            Some(tokens) => tokens,
+            None => return Err(ErrorIO::UnterminatedQuote(self.line_number)),
+        };
+        match tokens.separators {
+            // Blank lines and comments
             0 => Ok((Instruction::Nll, None)),
-            1 => Ok((Instruction::Nll, None)),
-            2 => {
-                let instruction = splitted[0];
-                let operands: Vec<&str> = splitted[1].split(',').collect();
+            1 => {
+                let instruction = tokens.head.text;
+                let operands: Vec<&str> = match &tokens.operands[..] {
+                    [operand] if operand.text.is_empty() => Vec::new(),
+                    operands => operands.iter().map(|operand| operand.text).collect(),
+                };
 
                 if instruction.is_empty() {
-                    Err(ErrorIO::EmptyInstruction(self.line_number))
-                } else if operands.is_empty() {
-                    Err(ErrorIO::NotEnoughOperands(self.line_number))
-                } else if operands.len() > 2 {
-                    Err(ErrorIO::TooMuchOperands(self.line_number))
-                } else if operands[0].is_empty() {
-                    Err(ErrorIO::EmptyOperand(self.line_number, 1))
-                } else if operands.len() > 1 && operands[1].is_empty() {
-                    Err(ErrorIO::EmptyOperand(self.line_number, 2))
+                    return Err(ErrorIO::EmptyInstruction(self.line_number));
+                }
+                let arity = match Arity::of(instruction) {
+                    Some(arity) => arity,
+                    None => {
+                        return Err(ErrorIO::UnknownInstruction(
+                            instruction.to_owned(),
+                            self.line_number,
+                        ))
+                    }
+                };
+                if !arity.accepts(operands.len()) {
+                    let instruction = instruction.to_owned();
+                    Err(if operands.len() < arity.min {
+                        ErrorIO::NotEnoughOperands(instruction, arity, self.line_number)
+                    } else {
+                        ErrorIO::TooMuchOperands(instruction, arity, self.line_number)
+                    })
+                } else if let Some(i) = operands.iter().position(|operand| operand.is_empty()) {
+                    Err(ErrorIO::EmptyOperand(self.line_number, i + 1))
                 } else {
                     self.match_instruction(instruction, operands)
                 }
@@ -89,94 +240,268 @@ impl ProgramFile {
         text_instruction: &str,
         operands: Vec<&str>,
     ) -> Result<(Instruction, Option<(String, usize)>), ErrorIO> {
-        let op0 = operands[0].to_owned();
-        match text_instruction {
-            "var" | "set" | "add" | "sub" | "mul" | "div" | "mod" | "cadd" | "csub" | "cmul"
-            | "cdiv" | "cmod" | "jmp" | "jne" => {
-                if operands.len() < 2 {
-                    return Err(ErrorIO::NotEnoughOperands(self.line_number));
-                }
-            }
-            "gto" | "flg" | "prt" => {
-                if operands.len() > 1 {
-                    return Err(ErrorIO::TooMuchOperands(self.line_number));
-                }
-            }
-            _ => (),
-        };
+        // Operands are counted before, from the arity of the instruction
+        let op0 = operands.first().copied().unwrap_or_default().to_owned();
         match text_instruction {
             "var" => Ok((
                 Instruction::Var {
-                    var: op0,
+                    var: Var::new(op0),
                     var_type: self.match_type(operands[1])?,
                 },
                 None,
             )),
             "set" => Ok((
                 Instruction::Set {
-                    var: op0,
+                    var: Var::new(op0),
                     value: self.match_var_or_value(operands[1])?,
                 },
                 None,
             )),
+            "add" if operands.len() == 3 => Ok((
+                Instruction::Add3 {
+                    var: Var::new(op0),
+                    op1: self.match_var_or_value(operands[1])?,
+                    op2: self.match_var_or_value(operands[2])?,
+                },
+                None,
+            )),
             "add" => Ok((
                 Instruction::Add {
-                    var: op0,
+                    var: Var::new(op0),
                     value: self.match_var_or_value(operands[1])?,
                 },
                 None,
             )),
+            "sub" if operands.len() == 3 => Ok((
+                Instruction::Sub3 {
+                    var: Var::new(op0),
+                    op1: self.match_var_or_value(operands[1])?,
+                    op2: self.match_var_or_value(operands[2])?,
+                },
+                None,
+            )),
             "sub" => Ok((
                 Instruction::Sub {
-                    var: op0,
+                    var: Var::new(op0),
                     value: self.match_var_or_value(operands[1])?,
                 },
                 None,
             )),
+            "mul" if operands.len() == 3 => Ok((
+                Instruction::Mul3 {
+                    var: Var::new(op0),
+                    op1: self.match_var_or_value(operands[1])?,
+                    op2: self.match_var_or_value(operands[2])?,
+                },
+                None,
+            )),
             "mul" => Ok((
                 Instruction::Mul {
-                    var: op0,
+                    var: Var::new(op0),
                     value: self.match_var_or_value(operands[1])?,
                 },
                 None,
             )),
+            "div" if operands.len() == 3 => Ok((
+                Instruction::Div3 {
+                    var: Var::new(op0),
+                    op1: self.match_var_or_value(operands[1])?,
+                    op2: self.match_var_or_value(operands[2])?,
+                },
+                None,
+            )),
             "div" => Ok((
                 Instruction::Div {
-                    var: op0,
+                    var: Var::new(op0),
                     value: self.match_var_or_value(operands[1])?,
                 },
                 None,
             )),
+            "mod" if operands.len() == 3 => Ok((
+                Instruction::Mod3 {
+                    var: Var::new(op0),
+                    op1: self.match_var_or_value(operands[1])?,
+                    op2: self.match_var_or_value(operands[2])?,
+                },
+                None,
+            )),
             "mod" => Ok((
                 Instruction::Mod {
-                    var: op0,
+                    var: Var::new(op0),
+                    value: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "and" => Ok((
+                Instruction::And {
+                    var: Var::new(op0),
                     value: self.match_var_or_value(operands[1])?,
                 },
                 None,
             )),
+            "or" => Ok((
+                Instruction::Or {
+                    var: Var::new(op0),
+                    value: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "xor" => Ok((
+                Instruction::Xor {
+                    var: Var::new(op0),
+                    value: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "shl" => Ok((
+                Instruction::Shl {
+                    var: Var::new(op0),
+                    value: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "shr" => Ok((
+                Instruction::Shr {
+                    var: Var::new(op0),
+                    value: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "not" => Ok((Instruction::Not { var: Var::new(op0) }, None)),
+            "itf" => Ok((
+                Instruction::Itf {
+                    var: Var::new(op0),
+                    value: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cti" => Ok((
+                Instruction::Cti {
+                    var: Var::new(op0),
+                    value: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "itc" => Ok((
+                Instruction::Itc {
+                    var: Var::new(op0),
+                    value: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "fti" => Ok((
+                Instruction::Fti {
+                    var: Var::new(op0),
+                    value: self.match_var_or_value(operands[1])?,
+                    rounding: match operands.get(2) {
+                        Some(rounding) => self.match_rounding(rounding)?,
+                        None => Rounding::default(),
+                    },
+                },
+                None,
+            )),
+            "del" => Ok((Instruction::Del { var: Var::new(op0) }, None)),
+            "inc" => Ok((Instruction::Inc { var: Var::new(op0) }, None)),
+            "dec" => Ok((Instruction::Dec { var: Var::new(op0) }, None)),
             "flg" => Ok((Instruction::Flg, Some((op0, self.line_number)))),
-            "gto" => Ok((Instruction::Gto { flag: op0 }, None)),
+            "gto" => Ok((
+                Instruction::Gto {
+                    flag: Flag::new(op0),
+                },
+                None,
+            )),
+            "cal" => Ok((
+                Instruction::Cal {
+                    flag: Flag::new(op0),
+                },
+                None,
+            )),
             "jmp" => Ok((
                 Instruction::Jmp {
-                    var: op0,
-                    flag: operands[1].to_owned(),
+                    var: Var::new(op0),
+                    flag: Flag::new(operands[1]),
+                },
+                None,
+            )),
+            // `jne` compares its operands when given a flag as third operand
+            "jne" if operands.len() == 3 => Ok((
+                Instruction::Jneq {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                    flag: Flag::new(operands[2]),
                 },
                 None,
             )),
             "jne" => Ok((
                 Instruction::Jne {
-                    var: op0,
-                    flag: operands[1].to_owned(),
+                    var: Var::new(op0),
+                    flag: Flag::new(operands[1]),
+                },
+                None,
+            )),
+            "jeq" => Ok((
+                Instruction::Jeq {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                    flag: Flag::new(operands[2]),
+                },
+                None,
+            )),
+            "jlt" => Ok((
+                Instruction::Jlt {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                    flag: Flag::new(operands[2]),
+                },
+                None,
+            )),
+            "jle" => Ok((
+                Instruction::Jle {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                    flag: Flag::new(operands[2]),
+                },
+                None,
+            )),
+            "jgt" => Ok((
+                Instruction::Jgt {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                    flag: Flag::new(operands[2]),
+                },
+                None,
+            )),
+            "jge" => Ok((
+                Instruction::Jge {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                    flag: Flag::new(operands[2]),
                 },
                 None,
             )),
             "nll" => Ok((Instruction::Nll, None)),
+            "ret" => Ok((Instruction::Ret, None)),
+            "pas" => Ok((Instruction::Pas, None)),
             "prt" => Ok((
                 Instruction::Prt {
                     value: self.match_var_or_value(&op0)?,
                 },
                 None,
             )),
+            "inp" => Ok((Instruction::Inp { var: Var::new(op0) }, None)),
+            "yld" => Ok((
+                Instruction::Yld {
+                    value: self.match_var_or_value(&op0)?,
+                },
+                None,
+            )),
+            "push" => Ok((
+                Instruction::Push {
+                    value: self.match_var_or_value(&op0)?,
+                },
+                None,
+            )),
+            "pop" => Ok((Instruction::Pop { var: Var::new(op0) }, None)),
 
             "cadd" => Ok((
                 Instruction::Cadd {
@@ -213,6 +538,89 @@ impl ProgramFile {
                 },
                 None,
             )),
+            "cand" => Ok((
+                Instruction::Cand {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cor" => Ok((
+                Instruction::Cor {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cxor" => Ok((
+                Instruction::Cxor {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cshl" => Ok((
+                Instruction::Cshl {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cshr" => Ok((
+                Instruction::Cshr {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cnot" => Ok((
+                Instruction::Cnot {
+                    value: self.match_var_or_value(operands[0])?,
+                },
+                None,
+            )),
+            "ceq" => Ok((
+                Instruction::Ceq {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cne" => Ok((
+                Instruction::Cne {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "clt" => Ok((
+                Instruction::Clt {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cle" => Ok((
+                Instruction::Cle {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cgt" => Ok((
+                Instruction::Cgt {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
+            "cge" => Ok((
+                Instruction::Cge {
+                    op1: self.match_var_or_value(operands[0])?,
+                    op2: self.match_var_or_value(operands[1])?,
+                },
+                None,
+            )),
             _ => Err(ErrorIO::UnknownInstruction(
                 text_instruction.to_owned(),
                 self.line_number,
@@ -229,11 +637,30 @@ impl ProgramFile {
         }
     }
 
+    fn match_rounding(&self, input: &str) -> Result<Rounding, ErrorIO> {
+        match input {
+            "trunc" => Ok(Rounding::Trunc),
+            "round" => Ok(Rounding::Round),
+            e => Err(ErrorIO::UnknownRounding(e.to_owned(), self.line_number)),
+        }
+    }
+
     fn match_var_or_value(&self, input: &str) -> Result<Val, ErrorIO> {
-        match input.get(0..1) {
-            Some(crate::VARIABLE_IDENTIFIER) => Ok(Val::Var(input.to_owned())),
-            Some(crate::CARRY_VARIABLE) => Ok(Val::Var(input.to_owned())),
-            Some(_) => Ok(Val::Value(input.to_owned())),
+        match input.chars().next() {
+            Some(_) if input.starts_with(crate::VARIABLE_IDENTIFIER) => {
+                Ok(Val::Var(Var::new(input)))
+            }
+            Some(_) if input == crate::CARRY_VARIABLE => Ok(Val::Var(Var::new(input))),
+            // Quoted values are always `chr`, e.g. `' '`
+            Some(_) if lexer::is_quoted(input) => match lexer::unquote(input) {
+                Some(value) => Ok(Val::Const(Const::Chr(value))),
+                None => Err(ErrorIO::InvalidValue(input.to_owned(), self.line_number)),
+            },
+            // Hardcoded values are typed here, e.g. `-5` is an `int`
+            Some(_) => match Const::parse(input) {
+                Some(value) => Ok(Val::Const(value)),
+                None => Err(ErrorIO::InvalidValue(input.to_owned(), self.line_number)),
+            },
             None => Err(ErrorIO::EmptyValue(self.line_number)),
         }
     }
@@ -242,14 +669,213 @@ impl ProgramFile {
 /// Contains types of IO errors
 #[derive(Debug)]
 pub enum ErrorIO {
-    CannotReadFile(&'static str),
-    ErrorParsingLine(usize),
-    NotEnoughOperands(usize),
-    TooMuchOperands(usize),
+    CannotReadFile(PathBuf, std::io::Error),
+    CannotReadSource(std::io::Error),
+    InvalidUtf8(usize),
+    /// An instruction written with less operands than its arity : name, arity, line
+    NotEnoughOperands(String, Arity, usize),
+    /// An instruction written with more operands than its arity : name, arity, line
+    TooMuchOperands(String, Arity, usize),
     TooMuchInstructionSeparator(usize),
     EmptyInstruction(usize),
     EmptyOperand(usize, usize),
     UnknownInstruction(String, usize),
     UnknownType(String, usize),
     EmptyValue(usize),
+    InvalidValue(String, usize),
+    /// A flag created twice : name, line of the first flag, line of the second one
+    DuplicateFlag(String, usize, usize),
+    UndefinedFlag(String, usize),
+    UnterminatedQuote(usize),
+    UnknownRounding(String, usize),
+}
+
+impl ErrorIO {
+    /// Returns the line the error was found at, if it is tied to one.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ErrorIO::CannotReadFile(..)
+            | ErrorIO::CannotReadSource(_)
+            | ErrorIO::InvalidUtf8(_) => None,
+            ErrorIO::NotEnoughOperands(_, _, line)
+            | ErrorIO::TooMuchOperands(_, _, line)
+            | ErrorIO::TooMuchInstructionSeparator(line)
+            | ErrorIO::EmptyInstruction(line)
+            | ErrorIO::EmptyOperand(line, _)
+            | ErrorIO::UnknownInstruction(_, line)
+            | ErrorIO::UnknownType(_, line)
+            | ErrorIO::EmptyValue(line)
+            | ErrorIO::InvalidValue(_, line)
+            | ErrorIO::DuplicateFlag(_, _, line)
+            | ErrorIO::UndefinedFlag(_, line)
+            | ErrorIO::UnterminatedQuote(line)
+            | ErrorIO::UnknownRounding(_, line) => Some(*line),
+        }
+    }
+}
+
+impl Located for ErrorIO {
+    fn code(&self) -> &'static str {
+        match self {
+            ErrorIO::CannotReadFile(..) => "E0001",
+            ErrorIO::CannotReadSource(_) => "E0002",
+            ErrorIO::InvalidUtf8(_) => "E0003",
+            ErrorIO::NotEnoughOperands(..) => "E0005",
+            ErrorIO::TooMuchOperands(..) => "E0006",
+            ErrorIO::TooMuchInstructionSeparator(_) => "E0007",
+            ErrorIO::EmptyInstruction(_) => "E0008",
+            ErrorIO::EmptyOperand(..) => "E0009",
+            ErrorIO::UnknownInstruction(..) => "E0010",
+            ErrorIO::UnknownType(..) => "E0011",
+            ErrorIO::EmptyValue(_) => "E0012",
+            ErrorIO::InvalidValue(..) => "E0013",
+            ErrorIO::DuplicateFlag(..) => "E0014",
+            ErrorIO::UndefinedFlag(..) => "E0015",
+            ErrorIO::UnterminatedQuote(_) => "E0016",
+            ErrorIO::UnknownRounding(..) => "E0017",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            ErrorIO::CannotReadFile(path, e) => {
+                format!("cannot read file `{}` : {}", path.display(), e)
+            }
+            ErrorIO::CannotReadSource(e) => format!("cannot read program source : {}", e),
+            ErrorIO::InvalidUtf8(offset) => {
+                format!("program source is not valid UTF-8 (byte {})", offset)
+            }
+            ErrorIO::NotEnoughOperands(ins, arity, _) => {
+                format!("not enough operands, `{}` expects {}", ins, arity)
+            }
+            ErrorIO::TooMuchOperands(ins, arity, _) => {
+                format!("too many operands, `{}` expects {}", ins, arity)
+            }
+            ErrorIO::TooMuchInstructionSeparator(_) => {
+                "more than one `:` separator on the same line".to_owned()
+            }
+            ErrorIO::EmptyInstruction(_) => "empty instruction name".to_owned(),
+            ErrorIO::EmptyOperand(_, n) => format!("operand {} is empty", n),
+            ErrorIO::UnknownInstruction(ins, _) => format!("unknown instruction `{}`", ins),
+            ErrorIO::UnknownType(t, _) => {
+                format!("unknown type `{}`, expected `int`, `flt` or `chr`", t)
+            }
+            ErrorIO::EmptyValue(_) => "empty value".to_owned(),
+            ErrorIO::InvalidValue(val, _) => {
+                format!("`{}` is neither an `int`, a `flt` nor a `chr`", val)
+            }
+            ErrorIO::DuplicateFlag(flag, first, _) => {
+                format!(
+                    "flag `{}` redefined (first created on line {})",
+                    flag,
+                    first + 1
+                )
+            }
+            ErrorIO::UndefinedFlag(flag, _) => format!("flag `{}` is never created", flag),
+            ErrorIO::UnterminatedQuote(_) => "quote is never closed".to_owned(),
+            ErrorIO::UnknownRounding(r, _) => {
+                format!("unknown rounding `{}`, expected `trunc` or `round`", r)
+            }
+        }
+    }
+
+    fn location(&self) -> Option<(usize, Highlight)> {
+        let highlight = match self {
+            ErrorIO::EmptyInstruction(_) | ErrorIO::UnknownInstruction(..) => {
+                Highlight::Instruction
+            }
+            ErrorIO::EmptyOperand(_, n) => Highlight::Operand(n - 1),
+            ErrorIO::TooMuchOperands(_, arity, _) => Highlight::Operand(arity.max),
+            ErrorIO::UnknownType(..) => Highlight::Operand(1),
+            ErrorIO::UnknownRounding(..) => Highlight::Operand(2),
+            ErrorIO::InvalidValue(val, _)
+            | ErrorIO::DuplicateFlag(val, ..)
+            | ErrorIO::UndefinedFlag(val, _) => Highlight::Text(val.clone()),
+            _ => Highlight::Line,
+        };
+        self.line().map(|line| (line, highlight))
+    }
+}
+
+impl fmt::Display for ErrorIO {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // The line of the first definition is written after the one of the error
+            ErrorIO::DuplicateFlag(flag, first, line) => write!(
+                f,
+                "flag `{}` redefined at line {} (first created on line {})",
+                flag,
+                line + 1,
+                first + 1
+            ),
+            _ => match self.line() {
+                Some(line) => write!(f, "{} at line {}", self.message(), line + 1),
+                None => write!(f, "{}", self.message()),
+            },
+        }
+    }
+}
+
+impl std::error::Error for ErrorIO {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ErrorIO::CannotReadFile(_, e) | ErrorIO::CannotReadSource(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Where a flag is created and used
+#[derive(Debug, PartialEq, Clone)]
+pub struct FlagSites {
+    pub name: String,
+    /// Line of the `flg` creating the flag
+    pub definition: usize,
+    /// Lines of the instructions jumping to the flag
+    pub references: Vec<usize>,
+}
+
+/// Contains types of parse warnings : the program can still run
+#[derive(Debug, PartialEq, Clone)]
+pub enum Warning {
+    UnusedFlag(String, usize),
+}
+
+impl Warning {
+    /// Returns the line the warning was found at.
+    pub fn line(&self) -> usize {
+        match self {
+            Warning::UnusedFlag(_, line) => *line,
+        }
+    }
+}
+
+impl Located for Warning {
+    fn level(&self) -> &'static str {
+        "warning"
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            Warning::UnusedFlag(..) => "W0001",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Warning::UnusedFlag(flag, _) => format!("flag `{}` is never used", flag),
+        }
+    }
+
+    fn location(&self) -> Option<(usize, Highlight)> {
+        match self {
+            Warning::UnusedFlag(flag, line) => Some((*line, Highlight::Text(flag.clone()))),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}", self.message(), self.line() + 1)
+    }
 }
@@ -0,0 +1,126 @@
+use std::ops::Range;
+
+/// Starts a comment running to the end of the line, as `//`
+const COMMENT: char = '#';
+/// Delimits a `chr` literal, e.g. `' '` or `','`
+const QUOTE: char = '\'';
+
+/// A part of a line : an instruction name or an operand, without surrounding whitespaces
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Token<'a> {
+    pub text: &'a str,
+    /// Columns of the token in the line, counted in characters
+    pub columns: Range<usize>,
+}
+
+/// A line split into tokens, without its comment
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Line<'a> {
+    /// Text before the first `:`, or the whole line if there is none
+    pub head: Token<'a>,
+    /// Operands after the first `:`, separated by `,`
+    pub operands: Vec<Token<'a>>,
+    /// Number of `:` separators
+    pub separators: usize,
+}
+
+/// Splits a line into tokens. `:`, `,` and comments inside a quoted operand are part of the
+/// quoted literal, and whitespaces are only removed around tokens.
+///
+/// Returns `None` if a quote starting an operand is never closed.
+pub(crate) fn tokenize(text: &str) -> Option<Line<'_>> {
+    let mut head = None;
+    let mut operands = Vec::new();
+    let mut separators = 0;
+    let mut start = 0;
+    let mut end = text.len();
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            COMMENT => {
+                end = i;
+                break;
+            }
+            '/' if matches!(chars.peek(), Some((_, '/'))) => {
+                end = i;
+                break;
+            }
+            // Only a quote starting an operand opens a literal, so plain-text comments without
+            // `:` can still hold apostrophes
+            QUOTE if head.is_some() && text[start..i].trim().is_empty() => loop {
+                match chars.next() {
+                    Some((_, '\\')) => {
+                        chars.next();
+                    }
+                    Some((_, QUOTE)) => break,
+                    Some(_) => (),
+                    None => return None,
+                }
+            },
+            ':' => {
+                separators += 1;
+                if head.is_none() {
+                    head = Some(token(text, start..i));
+                    start = i + 1;
+                }
+            }
+            ',' if head.is_some() => {
+                operands.push(token(text, start..i));
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    let last = token(text, start..end);
+    let head = match head {
+        Some(head) => {
+            operands.push(last);
+            head
+        }
+        None => last,
+    };
+    Some(Line {
+        head,
+        operands,
+        separators,
+    })
+}
+
+/// Returns the token at the given byte range of `text`, without surrounding whitespaces
+fn token(text: &str, bytes: Range<usize>) -> Token<'_> {
+    let part = &text[bytes.clone()];
+    let start = bytes.start + (part.len() - part.trim_start().len());
+    let end = (bytes.end - (part.len() - part.trim_end().len())).max(start);
+    Token {
+        text: &text[start..end],
+        columns: text[..start].chars().count()..text[..end].chars().count(),
+    }
+}
+
+/// Returns the `chr` written as a quoted literal, e.g. `'a'`, `' '` or `'\n'`.
+///
+/// Returns `None` if `text` is not quoted, or does not hold exactly one character.
+pub(crate) fn unquote(text: &str) -> Option<char> {
+    let inner = text.strip_prefix(QUOTE)?.strip_suffix(QUOTE)?;
+    let mut chars = inner.chars();
+    let chr = match chars.next()? {
+        '\\' => match chars.next()? {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            c @ ('\\' | QUOTE) => c,
+            _ => return None,
+        },
+        c => c,
+    };
+    match chars.next() {
+        None => Some(chr),
+        Some(_) => None,
+    }
+}
+
+/// Returns `true` if the text is a quoted literal
+pub(crate) fn is_quoted(text: &str) -> bool {
+    text.starts_with(QUOTE)
+}
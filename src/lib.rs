@@ -1,8 +1,19 @@
+pub mod arithmetic;
+mod checker;
+mod compiler;
+pub mod debugger;
+pub mod diagnostics;
+mod error;
 pub mod instructions;
 mod io;
+mod lexer;
+pub mod output;
 pub mod runtime;
+pub use arithmetic::*;
+pub use error::*;
 pub use io::*;
 pub use memory::*;
+pub use output::*;
 pub use runtime::*;
 pub mod memory;
 
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::ops::Index;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Cll {
     Int(Option<i32>),
     Flt(Option<f64>),
@@ -21,41 +22,151 @@ impl fmt::Display for Cll {
     }
 }
 
-pub type Mem = HashMap<String, Cll>;
+impl Cll {
+    /// Returns the value of the cell without its type, as printed by `prt` in plain format
+    pub fn plain(&self) -> String {
+        match self {
+            Cll::Int(Some(val)) => val.to_string(),
+            Cll::Flt(Some(val)) => val.to_string(),
+            Cll::Chr(Some(val)) => val.to_string(),
+            _ => "unitialized".to_owned(),
+        }
+    }
+}
+
+/// Slot of the carry variable `-`, the first one of every program
+pub const CARRY_SLOT: usize = 0;
+
+/// Memory of a program : each variable is stored in the slot it was given when the program was
+/// compiled, and can still be looked up by its name.
+#[derive(Clone, Debug, Default)]
+pub struct Mem {
+    names: Vec<String>,
+    slots: HashMap<String, usize>,
+    cells: Vec<Option<Cll>>,
+}
+
+impl Mem {
+    /// Returns an empty memory, with a slot for each of the given variable names
+    pub fn new(names: Vec<String>) -> Self {
+        Self {
+            slots: names
+                .iter()
+                .enumerate()
+                .map(|(slot, name)| (name.clone(), slot))
+                .collect(),
+            cells: vec![None; names.len()],
+            names,
+        }
+    }
+
+    /// Returns the slot given to a variable
+    pub fn slot(&self, name: &str) -> Option<usize> {
+        self.slots.get(name).copied()
+    }
+
+    /// Returns the name of the variable stored in a slot
+    pub fn name(&self, slot: usize) -> Option<&str> {
+        self.names.get(slot).map(|name| name.as_str())
+    }
+
+    /// Returns a variable, if it exists
+    pub fn get(&self, name: &str) -> Option<&Cll> {
+        self.cell(self.slot(name)?)
+    }
+
+    /// Returns `true` if the variable exists
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Iterates over existing variables and their values
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Cll)> {
+        self.names
+            .iter()
+            .zip(self.cells.iter())
+            .filter_map(|(name, cll)| Some((name.as_str(), cll.as_ref()?)))
+    }
+
+    /// Returns the variable stored in a slot, if it exists
+    pub fn cell(&self, slot: usize) -> Option<&Cll> {
+        self.cells.get(slot)?.as_ref()
+    }
+
+    /// Returns the variable stored in a slot, if it exists
+    pub fn cell_mut(&mut self, slot: usize) -> Option<&mut Cll> {
+        self.cells.get_mut(slot)?.as_mut()
+    }
+
+    /// Creates or replaces the variable stored in a slot
+    pub fn set_cell(&mut self, slot: usize, cll: Cll) {
+        if slot >= self.cells.len() {
+            self.cells.resize(slot + 1, None);
+        }
+        self.cells[slot] = Some(cll);
+    }
+
+    /// Deletes the variable stored in a slot, and returns it if it existed
+    pub fn remove_cell(&mut self, slot: usize) -> Option<Cll> {
+        self.cells.get_mut(slot)?.take()
+    }
+}
+
+impl Index<&str> for Mem {
+    type Output = Cll;
+
+    /// Returns a variable, panics if it does not exist
+    fn index(&self, name: &str) -> &Cll {
+        match self.get(name) {
+            Some(cll) => cll,
+            None => panic!("variable `{}` does not exist", name),
+        }
+    }
+}
 
 /// Performs the given operation on two variables, and set the result into the first one
 /// We do not merge it with the very similar `get_and_set_carry` macro because we want to open only one time `op1`
-// e.g. : get_and_change!(self, var, value, |a, b| { a + b }); to add var and value
+// e.g. : get_and_change!(self, var, value, Operation::Add); to add var and value
 #[macro_export]
 macro_rules! get_and_change {
     ($prog:expr, $var_op:expr, $val_op:expr, $op:expr) => {
-        // TODO do NOT use `clone` on memory, find a way to do `self.memory.get(...)` while memory borrowed by `self.memory.get_mut(...)`
-        let old_mem = $prog.memory.clone();
-        match $prog.memory.get_mut($var_op) {
+        // The value of `val` is read before `var` is borrowed, as both can be the same variable
+        let source = match $val_op {
+            Val::Var(var) => $prog.memory.cell(var.slot()).cloned(),
+            _ => None,
+        };
+        match $prog.memory.cell_mut($var_op.slot()) {
             // ! If variable exists in memory
             Some(cll) => match cll {
                 // ! If variable is an `int`
                 Cll::Int(cll_val) => match $val_op {
                     // If `val` represents an hardcoded value
-                    Val::Value(str_val) => {
-                        let b: i32 = match str_val.parse() {
-                            // If `val` is successfully parsed, add `cll_val`'s value and `val`
-                            Ok(l) => l,
-                            // If `val` is not successfully parsed
-                            Err(_) => return Err(Error::CouldNotParseIntValue((*str_val).clone())),
+                    Val::Const(val) => {
+                        let b: i32 = match val.as_int() {
+                            Some(b) => b,
+                            // If `val` is not an `int`
+                            None => {
+                                return Err(Error::CouldNotParseIntValue(
+                                    val.to_string(),
+                                    $prog.lnb,
+                                ))
+                            }
                         };
                         let a: i32 = match cll_val {
                             Some(a) => *a,
                             None => {
-                                return Err(Error::VariableIsUninitialized($var_op.to_string(), 0))
+                                return Err(Error::VariableIsUninitialized(
+                                    $var_op.to_string(),
+                                    $prog.lnb,
+                                ))
                             }
                         };
                         // Set result into `cll_val`'s value
-                        *cll_val = Some($op(a, b))
+                        *cll_val = Some($op.int(a, b, $prog.arithmetic, $prog.lnb)?)
                     }
                     // If `val` represents a memory-stored variable
-                    Val::Var(name) => {
-                        match old_mem.get(name) {
+                    Val::Var(var) => {
+                        match source {
                             // If `val` exists and is an `int`
                             Some(Cll::Int(Some(b))) => {
                                 let a: i32 = match cll_val {
@@ -63,16 +174,16 @@ macro_rules! get_and_change {
                                     None => {
                                         return Err(Error::VariableIsUninitialized(
                                             $var_op.to_string(),
-                                            0,
+                                            $prog.lnb,
                                         ))
                                     }
                                 };
-                                *cll_val = Some($op(a, b))
+                                *cll_val = Some($op.int(a, b, $prog.arithmetic, $prog.lnb)?)
                             }
                             // If `val` exists but is uninitialized
                             Some(Cll::Int(None)) => {
                                 return Err(Error::VariableIsUninitialized(
-                                    name.to_string(),
+                                    var.to_string(),
                                     $prog.lnb,
                                 ))
                             }
@@ -81,7 +192,7 @@ macro_rules! get_and_change {
                             // If `val` does not exists
                             None => {
                                 return Err(Error::VariableDoesNotExists(
-                                    name.to_string(),
+                                    var.to_string(),
                                     $prog.lnb,
                                 ))
                             }
@@ -91,25 +202,32 @@ macro_rules! get_and_change {
                 // ! If variable is a `flt`
                 Cll::Flt(cll_val) => match $val_op {
                     // If `val` represents an hardcoded value
-                    Val::Value(str_val) => {
-                        let b: f64 = match str_val.parse() {
-                            // If `val` is successfully parsed, add `cll_val`'s value and `val`
-                            Ok(l) => l,
-                            // If `val` is not successfully parsed
-                            Err(_) => return Err(Error::CouldNotParseFltValue((*str_val).clone())),
+                    Val::Const(val) => {
+                        let b: f64 = match val.as_flt() {
+                            Some(b) => b,
+                            // If `val` is not a `flt`
+                            None => {
+                                return Err(Error::CouldNotParseFltValue(
+                                    val.to_string(),
+                                    $prog.lnb,
+                                ))
+                            }
                         };
                         let a: f64 = match cll_val {
                             Some(a) => *a,
                             None => {
-                                return Err(Error::VariableIsUninitialized($var_op.to_string(), 0))
+                                return Err(Error::VariableIsUninitialized(
+                                    $var_op.to_string(),
+                                    $prog.lnb,
+                                ))
                             }
                         };
                         // Set result into `cll_val`'s value
-                        *cll_val = Some($op(a, b))
+                        *cll_val = Some($op.flt(a, b, $prog.lnb)?)
                     }
                     // If `val` represents a memory-stored variable
-                    Val::Var(name) => {
-                        match old_mem.get(name) {
+                    Val::Var(var) => {
+                        match source {
                             // If `val` exists and is a `flt`
                             Some(Cll::Flt(Some(b))) => {
                                 let a: f64 = match cll_val {
@@ -117,16 +235,16 @@ macro_rules! get_and_change {
                                     None => {
                                         return Err(Error::VariableIsUninitialized(
                                             $var_op.to_string(),
-                                            0,
+                                            $prog.lnb,
                                         ))
                                     }
                                 };
-                                *cll_val = Some($op(a, b))
+                                *cll_val = Some($op.flt(a, b, $prog.lnb)?)
                             }
                             // If `val` exists but is uninitialized
                             Some(Cll::Flt(None)) => {
                                 return Err(Error::VariableIsUninitialized(
-                                    name.to_string(),
+                                    var.to_string(),
                                     $prog.lnb,
                                 ))
                             }
@@ -135,7 +253,7 @@ macro_rules! get_and_change {
                             // If `val` does not exists
                             None => {
                                 return Err(Error::VariableDoesNotExists(
-                                    name.to_string(),
+                                    var.to_string(),
                                     $prog.lnb,
                                 ))
                             }
@@ -146,7 +264,7 @@ macro_rules! get_and_change {
                 Cll::Chr(_) => return Err(Error::CannotApplyOperationsOnChar($prog.lnb)),
             },
             // ! If variable does not exists in memory
-            None => return Err(Error::VariableDoesNotExists((*$var_op).clone(), $prog.lnb)),
+            None => return Err(Error::VariableDoesNotExists($var_op.to_string(), $prog.lnb)),
         }
     };
 }
@@ -154,11 +272,37 @@ macro_rules! get_and_change {
 /// Similar to `get_and_change`, but set result into `carry` variable : `-`
 #[macro_export]
 macro_rules! get_and_set_carry {
+    ($prog:expr, $op1:expr, $op2:expr, $op:expr) => {
+        let result = $crate::apply_operation!($prog, $op1, $op2, $op);
+        $prog.memory.set_cell($crate::CARRY_SLOT, result);
+    };
+}
+
+/// Similar to `get_and_set_carry`, but set result into an existing variable of the same type
+// e.g. : get_and_set!(self, var, op1, op2, Operation::Add); to add op1 and op2 into var
+#[macro_export]
+macro_rules! get_and_set {
+    ($prog:expr, $var:expr, $op1:expr, $op2:expr, $op:expr) => {
+        let result = $crate::apply_operation!($prog, $op1, $op2, $op);
+        match ($prog.memory.cell_mut($var.slot()), result) {
+            (Some(Cll::Int(cll_val)), Cll::Int(val)) => *cll_val = val,
+            (Some(Cll::Flt(cll_val)), Cll::Flt(val)) => *cll_val = val,
+            (Some(_), _) => return Err(Error::VariablesDifferInType($prog.lnb)),
+            (None, _) => return Err(Error::VariableDoesNotExists($var.to_string(), $prog.lnb)),
+        }
+    };
+}
+
+/// Returns the result of an operation on two operands, as a new `Cll` of their type.
+///
+/// If one of them is a hardcoded value, it takes the type of the other one.
+#[macro_export]
+macro_rules! apply_operation {
     ($prog:expr, $op1:expr, $op2:expr, $op:expr) => {
         // Match `op1` as a variable or a value
         match $op1 {
             // op1 is a variable
-            Val::Var(var_1) => match $prog.memory.get(var_1) {
+            Val::Var(var_1) => match $prog.memory.cell(var_1.slot()) {
                 // op1 exists in memory
                 Some(var_1_cll) => match var_1_cll {
                     // op1 is uninitialized : error
@@ -168,7 +312,7 @@ macro_rules! get_and_set_carry {
                     // op1 is an `int`
                     Cll::Int(Some(var_1_value)) => match $op2 {
                         // op2 is a variable
-                        Val::Var(var_2) => match $prog.memory.get(var_2) {
+                        Val::Var(var_2) => match $prog.memory.cell(var_2.slot()) {
                             // op2 exists in memory
                             Some(var_2_cll) => match var_2_cll {
                                 // op2 is uninitialized : error
@@ -179,40 +323,40 @@ macro_rules! get_and_set_carry {
                                     ))
                                 }
                                 // op2 is an `int` too
-                                Cll::Int(Some(var_2_value)) => {
-                                    $prog.memory.insert(
-                                        "-".to_owned(),
-                                        Cll::Int(Some($op(var_1_value, var_2_value))),
-                                    );
-                                }
+                                Cll::Int(Some(var_2_value)) => Cll::Int(Some($op.int(
+                                    *var_1_value,
+                                    *var_2_value,
+                                    $prog.arithmetic,
+                                    $prog.lnb,
+                                )?)),
                                 // op2 is not an `int` : error
                                 _ => return Err(Error::VariablesDifferInType($prog.lnb)),
                             },
                             // op2 does not exists in memory : error
                             None => {
                                 return Err(Error::VariableDoesNotExists(
-                                    var_1.to_string(),
+                                    var_2.to_string(),
                                     $prog.lnb,
                                 ))
                             }
                         },
                         // op2 is a value
-                        Val::Value(val_2) => match val_2.parse::<i32>() {
-                            // if op2 could be parsed as `int`
-                            Ok(val_2_value) => {
-                                $prog.memory.insert(
-                                    "-".to_owned(),
-                                    Cll::Int(Some($op(var_1_value, val_2_value))),
-                                );
-                            }
-                            // if op2 could not be parsed : error
-                            Err(_) => return Err(Error::CannotDetermineReturnType($prog.lnb)),
+                        Val::Const(val_2) => match val_2.as_int() {
+                            // if op2 is an `int`
+                            Some(val_2_value) => Cll::Int(Some($op.int(
+                                *var_1_value,
+                                val_2_value,
+                                $prog.arithmetic,
+                                $prog.lnb,
+                            )?)),
+                            // if op2 is not an `int` : error
+                            None => return Err(Error::CannotDetermineReturnType($prog.lnb)),
                         },
                     },
                     // op1 is a `flt`
                     Cll::Flt(Some(var_1_value)) => match $op2 {
                         // op2 is a variable
-                        Val::Var(var_2) => match $prog.memory.get(var_2) {
+                        Val::Var(var_2) => match $prog.memory.cell(var_2.slot()) {
                             // op2 exists in memory
                             Some(var_2_cll) => match var_2_cll {
                                 // op2 is uninitialized : error
@@ -223,34 +367,30 @@ macro_rules! get_and_set_carry {
                                     ))
                                 }
                                 // op2 is a `flt` too
-                                Cll::Flt(Some(var_2_value)) => {
-                                    $prog.memory.insert(
-                                        crate::CARRY_VARIABLE.to_owned(),
-                                        Cll::Flt(Some($op(var_1_value, var_2_value))),
-                                    );
-                                }
+                                Cll::Flt(Some(var_2_value)) => Cll::Flt(Some($op.flt(
+                                    *var_1_value,
+                                    *var_2_value,
+                                    $prog.lnb,
+                                )?)),
                                 // op2 is not a `flt` : error
                                 _ => return Err(Error::VariablesDifferInType($prog.lnb)),
                             },
                             // op2 does not exists in memory : error
                             None => {
                                 return Err(Error::VariableDoesNotExists(
-                                    var_1.to_string(),
+                                    var_2.to_string(),
                                     $prog.lnb,
                                 ))
                             }
                         },
                         // op2 is a value
-                        Val::Value(val_2) => match val_2.parse::<f64>() {
-                            // if op2 could be parsed as `flt`
-                            Ok(val_2_value) => {
-                                $prog.memory.insert(
-                                    crate::CARRY_VARIABLE.to_owned(),
-                                    Cll::Flt(Some($op(var_1_value, val_2_value))),
-                                );
+                        Val::Const(val_2) => match val_2.as_flt() {
+                            // if op2 is a `flt`
+                            Some(val_2_value) => {
+                                Cll::Flt(Some($op.flt(*var_1_value, val_2_value, $prog.lnb)?))
                             }
-                            // if op2 could not be parsed : error
-                            Err(_) => return Err(Error::CannotDetermineReturnType($prog.lnb)),
+                            // if op2 is not a `flt` : error
+                            None => return Err(Error::CannotDetermineReturnType($prog.lnb)),
                         },
                     },
                     // op1 is a `chr` : error
@@ -260,60 +400,73 @@ macro_rules! get_and_set_carry {
                 None => return Err(Error::VariableDoesNotExists(var_1.to_string(), $prog.lnb)),
             },
             // op1 is a value
-            Val::Value(val_1) => match $op2 {
-                // op2 is a value : error
-                Val::Value(_) => return Err(Error::CannotDetermineReturnType($prog.lnb)),
+            Val::Const(val_1) => match $op2 {
                 // op2 is a variable
-                Val::Var(val_2) => match $prog.memory.get(val_2) {
+                Val::Var(var_2) => match $prog.memory.cell(var_2.slot()) {
                     // op2 exists in memory
-                    Some(val_2_cll) => match val_2_cll {
+                    Some(var_2_cll) => match var_2_cll {
                         // op2 is uninitialized
                         Cll::Int(None) | Cll::Flt(None) => {
-                            return Err(Error::VariableIsUninitialized(val_2.to_string(), $prog.lnb))
+                            return Err(Error::VariableIsUninitialized(var_2.to_string(), $prog.lnb))
                         }
                         // op2 is an `int`
-                        Cll::Int(Some(val_2_value)) => match val_1.parse::<i32>() {
-                            // if op1 could be parsed as `int`
-                            Ok(val_1_value) => {
-                                $prog.memory.insert(
-                                    crate::CARRY_VARIABLE.to_owned(),
-                                    Cll::Int(Some($op(val_1_value, val_2_value))),
-                                );
-                            }
-                            // if op1 could not be parsed : error
-                            Err(_) => return Err(Error::CannotDetermineReturnType($prog.lnb)),
+                        Cll::Int(Some(var_2_value)) => match val_1.as_int() {
+                            // if op1 is an `int`
+                            Some(val_1_value) => Cll::Int(Some($op.int(
+                                val_1_value,
+                                *var_2_value,
+                                $prog.arithmetic,
+                                $prog.lnb,
+                            )?)),
+                            // if op1 is not an `int` : error
+                            None => return Err(Error::CannotDetermineReturnType($prog.lnb)),
                         },
                         // op2 is a `flt`
-                        Cll::Flt(Some(val_2_value)) => match val_1.parse::<f64>() {
-                            // if op1 could be parsed as `flt`
-                            Ok(val_1_value) => {
-                                $prog.memory.insert(
-                                    crate::CARRY_VARIABLE.to_owned(),
-                                    Cll::Flt(Some($op(val_1_value, val_2_value))),
-                                );
+                        Cll::Flt(Some(var_2_value)) => match val_1.as_flt() {
+                            // if op1 is a `flt`
+                            Some(val_1_value) => {
+                                Cll::Flt(Some($op.flt(val_1_value, *var_2_value, $prog.lnb)?))
                             }
-                            // if op1 could not be parsed : error
-                            Err(_) => return Err(Error::CannotDetermineReturnType($prog.lnb)),
+                            // if op1 is not a `flt` : error
+                            None => return Err(Error::CannotDetermineReturnType($prog.lnb)),
                         },
                         // op2 is a `chr` : error
                         Cll::Chr(_) => return Err(Error::CannotApplyOperationsOnChar($prog.lnb)),
                     },
                     // op2 does not exist in memory : error
-                    _ => return Err(Error::VariableDoesNotExists(val_2.to_string(), $prog.lnb)),
+                    None => return Err(Error::VariableDoesNotExists(var_2.to_string(), $prog.lnb)),
                 },
+                // op2 is a value : error
+                _ => return Err(Error::CannotDetermineReturnType($prog.lnb)),
             },
         }
     };
 }
 
+/// Adds `$delta` to an `int` or `flt` variable, used by `inc` and `dec`
+// e.g. : offset_variable!(self, var, 1); to increment var
+#[macro_export]
+macro_rules! offset_variable {
+    ($prog:expr, $var:expr, $delta:expr) => {
+        match $prog.memory.cell_mut($var.slot()) {
+            Some(Cll::Int(Some(val))) => {
+                *val = Operation::Add.int(*val, $delta, $prog.arithmetic, $prog.lnb)?
+            }
+            Some(Cll::Flt(Some(val))) => *val += f64::from($delta),
+            Some(Cll::Chr(_)) => return Err(Error::CannotApplyOperationsOnChar($prog.lnb)),
+            Some(_) => return Err(Error::VariableIsUninitialized($var.to_string(), $prog.lnb)),
+            None => return Err(Error::VariableDoesNotExists($var.to_string(), $prog.lnb)),
+        }
+    };
+}
+
+/// Returns the line of `$flag` if `$op` holds for `$val`, the next line otherwise
 #[macro_export]
 macro_rules! compare_and_get_flag {
     ($prog:expr, $val:expr, $flag:expr, $op:expr) => {
         if $op($val) {
-            match $prog.file.flags.get($flag) {
-                Some(line) => *line,
-                None => return Err(Error::CouldNotFindFlag($flag.to_string(), $prog.lnb)),
-            }
+            $prog.jumped = true;
+            $flag.line()
         } else {
             $prog.lnb + 1
         }
@@ -0,0 +1,55 @@
+use super::memory::Cll;
+use std::io::{self, Write};
+
+/// Format used to print the values given to `prt`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OutputFormat {
+    /// Prints the line number, the variable name and the type along with the value :\
+    /// `l° 4 : &a    =   5     _int`
+    Debug,
+    /// Prints only the value : `5`
+    Plain,
+}
+
+/// Receives the values printed by the `prt` instruction
+pub trait Output {
+    /// Called by each `prt` with the current line, the name of the printed variable if any,
+    /// and the printed value.
+    fn print(&mut self, line: usize, name: Option<&str>, value: &Cll) -> io::Result<()>;
+}
+
+impl<F> Output for F
+where
+    F: FnMut(usize, Option<&str>, &Cll) -> io::Result<()>,
+{
+    fn print(&mut self, line: usize, name: Option<&str>, value: &Cll) -> io::Result<()> {
+        self(line, name, value)
+    }
+}
+
+/// Writes the printed values into any `Write`, one per line
+pub struct WriteOutput<W: Write> {
+    writer: W,
+    format: OutputFormat,
+}
+
+impl<W: Write> WriteOutput<W> {
+    /// Returns a new output writing into `writer` with the given format
+    pub fn new(writer: W, format: OutputFormat) -> Self {
+        Self { writer, format }
+    }
+}
+
+impl<W: Write> Output for WriteOutput<W> {
+    fn print(&mut self, line: usize, name: Option<&str>, value: &Cll) -> io::Result<()> {
+        match (self.format, name) {
+            (OutputFormat::Debug, Some(name)) => {
+                writeln!(self.writer, "l°{:2} : {:5} =   {}", line, name, value)
+            }
+            (OutputFormat::Debug, None) => {
+                writeln!(self.writer, "l°{:2} : value =   {}", line, value.plain())
+            }
+            (OutputFormat::Plain, _) => writeln!(self.writer, "{}", value.plain()),
+        }
+    }
+}
@@ -1,321 +1,1306 @@
+use super::arithmetic::{Arithmetic, Comparison, Operation};
+use super::compiler;
+use super::diagnostics::{self, Highlight, Located};
 use super::instructions::*;
 use super::memory::*;
+use super::output::{Output, OutputFormat, WriteOutput};
 use super::ProgramFile;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::time::Instant;
+
+/// Default maximum number of values on the stack
+pub const DEFAULT_MAX_STACK_DEPTH: usize = 1024;
+/// Default maximum number of nested subroutine calls
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 256;
 
 pub struct Program {
     file: ProgramFile,
+    code: Vec<Line>,
     pub lnb: usize,
     pub memory: Mem,
+    output: Box<dyn Output>,
+    input: Box<dyn BufRead>,
+    stack: Vec<Cll>,
+    max_stack_depth: usize,
+    calls: Vec<usize>,
+    max_call_depth: usize,
+    hooks: Vec<Box<dyn Hook>>,
+    /// Set when the last executed instruction jumped, even to the following line
+    jumped: bool,
+    executed: u64,
+    fuel: Option<u64>,
+    deadline: Option<Instant>,
+    arithmetic: Arithmetic,
 }
 
 impl Program {
-    /// Constructs a new program from the given `ProgramFile`
-    pub fn from(file: ProgramFile) -> Self {
-        Self {
+    /// Compiles the given parsed `ProgramFile` into a new program.
+    ///
+    /// Fails if a flag used by the program is never created, or if a hardcoded value is neither
+    /// an `int`, a `flt` nor a `chr`.
+    pub fn compile(file: ProgramFile) -> Result<Self, Error> {
+        let (code, memory) = compiler::compile(&file)?;
+        Ok(Self {
             file,
+            code,
             lnb: 0,
-            memory: Mem::default(),
+            memory,
+            output: Box::new(WriteOutput::new(io::stdout(), OutputFormat::Debug)),
+            input: Box::new(BufReader::new(io::stdin())),
+            stack: Vec::new(),
+            max_stack_depth: DEFAULT_MAX_STACK_DEPTH,
+            calls: Vec::new(),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            hooks: Vec::new(),
+            jumped: false,
+            executed: 0,
+            fuel: None,
+            deadline: None,
+            arithmetic: Arithmetic::default(),
+        })
+    }
+
+    /// Reads the values asked by `inp` from `input` instead of `stdin`, one per line
+    pub fn with_input(mut self, input: impl BufRead + 'static) -> Self {
+        self.input = Box::new(input);
+        self
+    }
+
+    /// Sends the values printed by `prt` to the given output instead of `stdout`
+    pub fn with_output(mut self, output: impl Output + 'static) -> Self {
+        self.output = Box::new(output);
+        self
+    }
+
+    /// Writes the values printed by `prt` into `writer`, using the given format
+    pub fn with_writer(self, writer: impl Write + 'static, format: OutputFormat) -> Self {
+        self.with_output(WriteOutput::new(writer, format))
+    }
+
+    /// Sets the maximum number of values the stack can hold before `push` fails
+    pub fn with_max_stack_depth(mut self, depth: usize) -> Self {
+        self.max_stack_depth = depth;
+        self
+    }
+
+    /// Returns the values on the stack, the top of the stack being the last one
+    pub fn stack(&self) -> &[Cll] {
+        &self.stack
+    }
+
+    /// Sets the maximum number of nested `cal` before the program fails
+    pub fn with_max_call_depth(mut self, depth: usize) -> Self {
+        self.max_call_depth = depth;
+        self
+    }
+
+    /// Returns the lines each pending `ret` will return to, the innermost call being the last one
+    pub fn call_stack(&self) -> &[usize] {
+        &self.calls
+    }
+
+    /// Adds a hook called before and after each instruction
+    pub fn with_hook(mut self, hook: impl Hook + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Sets how operations on `int` handle overflows
+    pub fn with_arithmetic(mut self, arithmetic: Arithmetic) -> Self {
+        self.arithmetic = arithmetic;
+        self
+    }
+
+    /// Limits the number of instructions the program can execute, see `add_fuel`
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = Some(fuel);
+        self
+    }
+
+    /// Stops the program with an error if it is still running at `deadline`
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Allows a program to execute `fuel` more instructions, e.g. to resume it once its fuel is
+    /// exhausted
+    pub fn add_fuel(&mut self, fuel: u64) {
+        self.fuel = Some(self.fuel.unwrap_or(0).saturating_add(fuel));
+    }
+
+    /// Sets or removes the deadline of the program
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    /// Returns the number of instructions the program executed so far
+    pub fn executed(&self) -> u64 {
+        self.executed
+    }
+
+    /// Returns the number of instructions the program can still execute, if it is limited
+    pub fn fuel(&self) -> Option<u64> {
+        self.fuel
+    }
+
+    /// Returns the file the program was constructed from
+    pub fn file(&self) -> &ProgramFile {
+        &self.file
+    }
+
+    /// Returns the compiled lines of the program
+    pub fn code(&self) -> &[Line] {
+        &self.code
+    }
+
+    /// Renders the given runtime error as a diagnostic pointing into the compiled code.
+    pub fn render(&self, error: &impl Located) -> String {
+        diagnostics::render(&self.file, &self.code, error)
+    }
+
+    /// Runs the program from its current line, until it finishes, pauses or yields a value
+    pub fn run(&mut self) -> Result<State, Error> {
+        loop {
+            // Hooks are given a `Step` for each instruction, which is only built when needed
+            if !self.hooks.is_empty() {
+                if let Some(state) = self.step()?.state {
+                    return Ok(state);
+                }
+                continue;
+            }
+            if self.lnb >= self.code.len() {
+                return Ok(State::Finished(self.lnb));
+            }
+            self.check_limits()?;
+            let state = self.execute()?;
+            self.count_executed();
+            match state {
+                Some(state) => return Ok(state),
+                None if self.lnb >= self.code.len() => return Ok(State::Finished(self.lnb)),
+                None => (),
+            }
+        }
+    }
+
+    /// Executes exactly one instruction, the one at the current line, and reports what it did.
+    ///
+    /// Does nothing if the program already reached its end, and reports it as `Finished`.
+    pub fn step(&mut self) -> Result<Step, Error> {
+        let line = self.lnb;
+        if line >= self.code.len() {
+            return Ok(Step {
+                line,
+                next: line,
+                written: Vec::new(),
+                jump: None,
+                state: Some(State::Finished(line)),
+            });
+        }
+
+        self.check_limits()?;
+
+        // Hooks are taken out of the program so that they can look at it
+        let mut hooks = std::mem::take(&mut self.hooks);
+        for hook in hooks.iter_mut() {
+            hook.before(self, line, &self.code[line].instruction);
         }
+        self.jumped = false;
+        let state = match self.execute() {
+            Ok(state) => state,
+            Err(e) => {
+                self.hooks = hooks;
+                return Err(e);
+            }
+        };
+        self.count_executed();
+        let step = Step {
+            line,
+            next: self.lnb,
+            written: self.code[line]
+                .instruction
+                .written_variable()
+                .map(|var| var.to_owned())
+                .into_iter()
+                .collect(),
+            jump: if self.jumped { Some(self.lnb) } else { None },
+            state: match state {
+                None if self.lnb >= self.code.len() => Some(State::Finished(self.lnb)),
+                state => state,
+            },
+        };
+        for hook in hooks.iter_mut() {
+            hook.after(self, &step);
+        }
+        self.hooks = hooks;
+        Ok(step)
     }
 
-    /// Runs the program
-    // TODO understand the clippy lint `block_in_if_condition_stmt` used for `compare_and_get_flag` macro
-    #[allow(clippy::block_in_if_condition_stmt)]
-    // TODO get around lint 'cannot borrow self.memory as mutable because it is also borrowed as immutable'
-    #[allow(mutable_borrow_reservation_conflict)]
+    /// Fails if the program has no fuel left, or if its deadline is exceeded
+    fn check_limits(&self) -> Result<(), Error> {
+        if self.fuel == Some(0) {
+            return Err(Error::FuelExhausted {
+                executed: self.executed,
+                line: self.lnb,
+            });
+        }
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                return Err(Error::DeadlineExceeded {
+                    executed: self.executed,
+                    line: self.lnb,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts an executed instruction, and consumes its fuel
+    fn count_executed(&mut self) {
+        self.executed += 1;
+        if let Some(fuel) = self.fuel.as_mut() {
+            *fuel -= 1;
+        }
+    }
+
+    /// Executes the instruction at the current line and moves to the next one to execute
     #[allow(clippy::cognitive_complexity)]
     #[allow(unreachable_patterns)]
-    pub fn run(&mut self) -> Result<usize, Error> {
-        let mut line: &Instruction;
-        while self.lnb < self.file.lines.len() {
-            line = &self.file.lines[self.lnb];
-            // Instruction matcher
-            self.lnb = match line {
-                // ! ------- `VAR` -------------
-                // `var` instruction
-                Instruction::Var { var, var_type } => {
-                    match var_type {
-                        Type::Int => {
-                            self.memory.insert(var.clone(), Cll::Int(None));
-                        }
-                        Type::Flt => {
-                            self.memory.insert(var.clone(), Cll::Flt(None));
-                        }
-                        Type::Chr => {
-                            self.memory.insert(var.clone(), Cll::Chr(None));
-                        }
-                    };
-                    self.lnb + 1
-                }
+    fn execute(&mut self) -> Result<Option<State>, Error> {
+        let line = &self.code[self.lnb].instruction;
+        // Instruction matcher
+        self.lnb = match line {
+            // ! ------- `VAR` -------------
+            // `var` instruction
+            Instruction::Var { var, var_type } => {
+                match var_type {
+                    Type::Int => {
+                        self.memory.set_cell(var.slot(), Cll::Int(None));
+                    }
+                    Type::Flt => {
+                        self.memory.set_cell(var.slot(), Cll::Flt(None));
+                    }
+                    Type::Chr => {
+                        self.memory.set_cell(var.slot(), Cll::Chr(None));
+                    }
+                };
+                self.lnb + 1
+            }
 
-                // ! ------- `SET` -------------
-                // `set` instruction
-                // TODO do NOT use `clone` on memory, find a way to do `self.memory.get(...)` while memory borrowed by `self.memory.get_mut(...)`
-                Instruction::Set { var, value } => {
-                    let old_mem = self.memory.clone();
-                    match self.memory.get_mut(var) {
-                        // If variable exists in memory
-                        Some(cll) => match cll {
-                            // If variable is an `int`
-                            Cll::Int(cll_val) => match value {
-                                // If `val` represents an hardcoded value
-                                Val::Value(str_val) => {
-                                    *cll_val = match str_val.parse() {
-                                        Ok(l) => Some(l),
-                                        Err(_) => {
-                                            return Err(Error::CouldNotParseIntValue(
-                                                str_val.to_string(),
-                                            ))
-                                        }
-                                    };
-                                }
-                                // If `val` represents a memory-stored variable
-                                Val::Var(name) => {
-                                    let mem = old_mem.get(name);
-                                    match mem {
-                                        // If `val` exists and is an `int`
-                                        Some(Cll::Int(val)) => *cll_val = *val,
-                                        // If `val` exists but is not an `int`
-                                        Some(_) => {
-                                            return Err(Error::VariablesDifferInType(self.lnb))
-                                        }
-                                        // If `val` is not defined
-                                        None => {
-                                            return Err(Error::VariableIsUninitialized(
-                                                name.to_string(),
-                                                self.lnb,
-                                            ))
-                                        }
+            // ! ------- `SET` -------------
+            // `set` instruction
+            Instruction::Set { var, value } => {
+                // The value of `val` is read before `var` is borrowed, as both can be the same variable
+                let source = match value {
+                    Val::Var(name) => self.memory.cell(name.slot()).cloned(),
+                    _ => None,
+                };
+                match self.memory.cell_mut(var.slot()) {
+                    // If variable exists in memory
+                    Some(cll) => match cll {
+                        // If variable is an `int`
+                        Cll::Int(cll_val) => match value {
+                            // If `val` represents an hardcoded value
+                            Val::Const(val) => {
+                                *cll_val = match val.as_int() {
+                                    Some(l) => Some(l),
+                                    None => {
+                                        return Err(Error::CouldNotParseIntValue(
+                                            val.to_string(),
+                                            self.lnb,
+                                        ))
                                     }
-                                }
-                            },
-                            // If variable is a `flt`
-                            Cll::Flt(cll_val) => match value {
-                                // If `val` represents an hardcoded value
-                                Val::Value(str_val) => {
-                                    *cll_val = match str_val.parse() {
-                                        Ok(l) => Some(l),
-                                        Err(_) => {
-                                            return Err(Error::CouldNotParseFltValue(
-                                                str_val.to_string(),
-                                            ))
-                                        }
-                                    };
-                                }
-                                // If `val` represents a memory-stored variable
-                                Val::Var(name) => {
-                                    let mem = old_mem.get(name);
-                                    match mem {
-                                        // If `val` exists and is an `int`
-                                        Some(Cll::Flt(val)) => *cll_val = *val,
-                                        // If `val` exists but is not an `int`
-                                        Some(_) => {
-                                            return Err(Error::VariablesDifferInType(self.lnb))
-                                        }
-                                        // If `val` is not defined
-                                        None => {
-                                            return Err(Error::VariableIsUninitialized(
-                                                name.to_string(),
-                                                self.lnb,
-                                            ))
-                                        }
+                                };
+                            }
+                            // If `val` represents a memory-stored variable
+                            Val::Var(name) => {
+                                match source {
+                                    // If `val` exists and is an `int`
+                                    Some(Cll::Int(val)) => *cll_val = val,
+                                    // If `val` exists but is not an `int`
+                                    Some(_) => return Err(Error::VariablesDifferInType(self.lnb)),
+                                    // If `val` is not defined
+                                    None => {
+                                        return Err(Error::VariableIsUninitialized(
+                                            name.to_string(),
+                                            self.lnb,
+                                        ))
                                     }
                                 }
-                            }, // If variable is a `chr`
-                            Cll::Chr(cll_val) => match value {
-                                // If `val` represents an hardcoded value
-                                Val::Value(str_val) => {
-                                    *cll_val = match str_val.parse() {
-                                        Ok(l) => Some(l),
-                                        Err(_) => {
-                                            return Err(Error::CouldNotParseChrValue(
-                                                str_val.to_string(),
-                                            ))
-                                        }
-                                    };
+                            }
+                        },
+                        // If variable is a `flt`
+                        Cll::Flt(cll_val) => match value {
+                            // If `val` represents an hardcoded value
+                            Val::Const(val) => {
+                                *cll_val = match val.as_flt() {
+                                    Some(l) => Some(l),
+                                    None => {
+                                        return Err(Error::CouldNotParseFltValue(
+                                            val.to_string(),
+                                            self.lnb,
+                                        ))
+                                    }
+                                };
+                            }
+                            // If `val` represents a memory-stored variable
+                            Val::Var(name) => {
+                                match source {
+                                    // If `val` exists and is an `int`
+                                    Some(Cll::Flt(val)) => *cll_val = val,
+                                    // If `val` exists but is not an `int`
+                                    Some(_) => return Err(Error::VariablesDifferInType(self.lnb)),
+                                    // If `val` is not defined
+                                    None => {
+                                        return Err(Error::VariableIsUninitialized(
+                                            name.to_string(),
+                                            self.lnb,
+                                        ))
+                                    }
                                 }
-                                // If `val` represents a memory-stored variable
-                                Val::Var(name) => {
-                                    let mem = old_mem.get(name);
-                                    match mem {
-                                        // If `val` exists and is an `int`
-                                        Some(Cll::Chr(val)) => *cll_val = *val,
-                                        // If `val` exists but is not an `int`
-                                        Some(_) => {
-                                            return Err(Error::VariablesDifferInType(self.lnb))
-                                        }
-                                        // If `val` is not defined
-                                        None => {
-                                            return Err(Error::VariableIsUninitialized(
-                                                name.to_string(),
-                                                self.lnb,
-                                            ))
-                                        }
+                            }
+                        }, // If variable is a `chr`
+                        Cll::Chr(cll_val) => match value {
+                            // If `val` represents an hardcoded value
+                            Val::Const(val) => {
+                                *cll_val = match val.as_chr() {
+                                    Some(l) => Some(l),
+                                    None => {
+                                        return Err(Error::CouldNotParseChrValue(
+                                            val.to_string(),
+                                            self.lnb,
+                                        ))
+                                    }
+                                };
+                            }
+                            // If `val` represents a memory-stored variable
+                            Val::Var(name) => {
+                                match source {
+                                    // If `val` exists and is an `int`
+                                    Some(Cll::Chr(val)) => *cll_val = val,
+                                    // If `val` exists but is not an `int`
+                                    Some(_) => return Err(Error::VariablesDifferInType(self.lnb)),
+                                    // If `val` is not defined
+                                    None => {
+                                        return Err(Error::VariableIsUninitialized(
+                                            name.to_string(),
+                                            self.lnb,
+                                        ))
                                     }
                                 }
-                            },
+                            }
                         },
-                        // If variable does not exists in memory
-                        None => return Err(Error::VariableDoesNotExists((*var).clone(), self.lnb)),
-                    };
-                    self.lnb + 1
+                    },
+                    // If variable does not exists in memory
+                    None => return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb)),
+                };
+                self.lnb + 1
+            }
+
+            // ! ------- `ADD` -------------
+            // `add` instruction
+            Instruction::Add { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::Add);
+                self.lnb + 1
+            }
+
+            // ! ------- `SUB` -------------
+            // `sub` instruction
+            Instruction::Sub { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::Sub);
+                self.lnb + 1
+            }
+            // ! ------- `MUL` -------------
+            // `mul` instruction
+            Instruction::Mul { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::Mul);
+                self.lnb + 1
+            }
+
+            // ! ------- `DIV` -------------
+            // `div` instruction
+            Instruction::Div { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::Div);
+                self.lnb + 1
+            }
+
+            // ! ------- `MOD` -------------
+            // `mod` instruction
+            Instruction::Mod { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::Mod);
+                self.lnb + 1
+            }
+
+            // ! ------- `AND` -------------
+            // `and` instruction
+            Instruction::And { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::And);
+                self.lnb + 1
+            }
+
+            // ! ------- `OR` -------------
+            // `or` instruction
+            Instruction::Or { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::Or);
+                self.lnb + 1
+            }
+
+            // ! ------- `XOR` -------------
+            // `xor` instruction
+            Instruction::Xor { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::Xor);
+                self.lnb + 1
+            }
+
+            // ! ------- `SHL` -------------
+            // `shl` instruction
+            Instruction::Shl { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::Shl);
+                self.lnb + 1
+            }
+
+            // ! ------- `SHR` -------------
+            // `shr` instruction
+            Instruction::Shr { var, value } => {
+                crate::get_and_change!(self, var, value, Operation::Shr);
+                self.lnb + 1
+            }
+
+            // ! ------- `NOT` -------------
+            // `not` instruction
+            Instruction::Not { var } => {
+                match self.memory.cell_mut(var.slot()) {
+                    Some(Cll::Int(Some(val))) => *val = !*val,
+                    Some(Cll::Flt(_)) => return Err(Error::CannotApplyBitwiseOnFlt(self.lnb)),
+                    Some(Cll::Chr(_)) => return Err(Error::CannotApplyOperationsOnChar(self.lnb)),
+                    Some(_) => {
+                        return Err(Error::VariableIsUninitialized(var.to_string(), self.lnb))
+                    }
+                    None => return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb)),
                 }
+                self.lnb + 1
+            }
 
-                // ! ------- `ADD` -------------
-                // `add` instruction
-                Instruction::Add { var, value } => {
-                    crate::get_and_change!(self, var, value, |a, b| { a + b });
-                    self.lnb + 1
+            // ! ------- `ITF` -------------
+            // `itf` instruction
+            Instruction::Itf { var, value } => {
+                let converted = match Self::value_as(&self.memory, value, Type::Int, self.lnb)? {
+                    Cll::Int(Some(val)) => f64::from(val),
+                    _ => return Err(Error::VariablesDifferInType(self.lnb)),
+                };
+                Self::store(&mut self.memory, var, Cll::Flt(Some(converted)), self.lnb)?;
+                self.lnb + 1
+            }
+
+            // ! ------- `FTI` -------------
+            // `fti` instruction
+            Instruction::Fti {
+                var,
+                value,
+                rounding,
+            } => {
+                let val = match Self::value_as(&self.memory, value, Type::Flt, self.lnb)? {
+                    Cll::Flt(Some(val)) => val,
+                    _ => return Err(Error::VariablesDifferInType(self.lnb)),
+                };
+                let rounded = rounding.apply(val);
+                // NaN fails both comparisons
+                if !(rounded >= f64::from(i32::MIN) && rounded <= f64::from(i32::MAX)) {
+                    return Err(Error::CannotConvertToInt(val, self.lnb));
                 }
+                Self::store(
+                    &mut self.memory,
+                    var,
+                    Cll::Int(Some(rounded as i32)),
+                    self.lnb,
+                )?;
+                self.lnb + 1
+            }
 
-                // ! ------- `SUB` -------------
-                // `sub` instruction
-                Instruction::Sub { var, value } => {
-                    crate::get_and_change!(self, var, value, |a, b| { a - b });
-                    self.lnb + 1
+            // ! ------- `CTI` -------------
+            // `cti` instruction
+            Instruction::Cti { var, value } => {
+                let converted = match Self::value_as(&self.memory, value, Type::Chr, self.lnb)? {
+                    Cll::Chr(Some(val)) => val as i32,
+                    _ => return Err(Error::VariablesDifferInType(self.lnb)),
+                };
+                Self::store(&mut self.memory, var, Cll::Int(Some(converted)), self.lnb)?;
+                self.lnb + 1
+            }
+
+            // ! ------- `ITC` -------------
+            // `itc` instruction
+            Instruction::Itc { var, value } => {
+                let val = match Self::value_as(&self.memory, value, Type::Int, self.lnb)? {
+                    Cll::Int(Some(val)) => val,
+                    _ => return Err(Error::VariablesDifferInType(self.lnb)),
+                };
+                let converted = match u32::try_from(val).ok().and_then(char::from_u32) {
+                    Some(converted) => converted,
+                    None => return Err(Error::InvalidCodePoint(val, self.lnb)),
+                };
+                Self::store(&mut self.memory, var, Cll::Chr(Some(converted)), self.lnb)?;
+                self.lnb + 1
+            }
+
+            // ! ------- `DEL` -------------
+            // `del` instruction
+            Instruction::Del { var } => match self.memory.remove_cell(var.slot()) {
+                Some(_) => self.lnb + 1,
+                None => return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb)),
+            },
+
+            // ! ------- `INC` -------------
+            // `inc` instruction
+            Instruction::Inc { var } => {
+                crate::offset_variable!(self, var, 1);
+                self.lnb + 1
+            }
+
+            // ! ------- `DEC` -------------
+            // `dec` instruction
+            Instruction::Dec { var } => {
+                crate::offset_variable!(self, var, -1);
+                self.lnb + 1
+            }
+
+            // ! ------- `ADD3` -------------
+            // `add` instruction, with three operands
+            Instruction::Add3 { var, op1, op2 } => {
+                crate::get_and_set!(self, var, op1, op2, Operation::Add);
+                self.lnb + 1
+            }
+
+            // ! ------- `SUB3` -------------
+            // `sub` instruction, with three operands
+            Instruction::Sub3 { var, op1, op2 } => {
+                crate::get_and_set!(self, var, op1, op2, Operation::Sub);
+                self.lnb + 1
+            }
+
+            // ! ------- `MUL3` -------------
+            // `mul` instruction, with three operands
+            Instruction::Mul3 { var, op1, op2 } => {
+                crate::get_and_set!(self, var, op1, op2, Operation::Mul);
+                self.lnb + 1
+            }
+
+            // ! ------- `DIV3` -------------
+            // `div` instruction, with three operands
+            Instruction::Div3 { var, op1, op2 } => {
+                crate::get_and_set!(self, var, op1, op2, Operation::Div);
+                self.lnb + 1
+            }
+
+            // ! ------- `MOD3` -------------
+            // `mod` instruction, with three operands
+            Instruction::Mod3 { var, op1, op2 } => {
+                crate::get_and_set!(self, var, op1, op2, Operation::Mod);
+                self.lnb + 1
+            }
+
+            // ! ------- `CADD` -------------
+            // `cadd` instruction
+            Instruction::Cadd { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::Add);
+                self.lnb + 1
+            }
+
+            // ! ------- `CSUB` -------------
+            // `csub` instruction
+            Instruction::Csub { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::Sub);
+                self.lnb + 1
+            }
+
+            // ! ------- `CMUL` -------------
+            // `cmul` instruction
+            Instruction::Cmul { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::Mul);
+                self.lnb + 1
+            }
+
+            // ! ------- `CDIV` -------------
+            // `cdiv` instruction
+            Instruction::Cdiv { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::Div);
+                self.lnb + 1
+            }
+
+            // ! ------- `CMOD` -------------
+            // `cmod` instruction
+            Instruction::Cmod { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::Mod);
+                self.lnb + 1
+            }
+
+            // ! ------- `CAND` -------------
+            // `cand` instruction
+            Instruction::Cand { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::And);
+                self.lnb + 1
+            }
+
+            // ! ------- `COR` -------------
+            // `cor` instruction
+            Instruction::Cor { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::Or);
+                self.lnb + 1
+            }
+
+            // ! ------- `CXOR` -------------
+            // `cxor` instruction
+            Instruction::Cxor { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::Xor);
+                self.lnb + 1
+            }
+
+            // ! ------- `CSHL` -------------
+            // `cshl` instruction
+            Instruction::Cshl { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::Shl);
+                self.lnb + 1
+            }
+
+            // ! ------- `CSHR` -------------
+            // `cshr` instruction
+            Instruction::Cshr { op1, op2 } => {
+                crate::get_and_set_carry!(self, op1, op2, Operation::Shr);
+                self.lnb + 1
+            }
+
+            // ! ------- `CNOT` -------------
+            // `cnot` instruction
+            Instruction::Cnot { value } => {
+                let val = match Self::value_of(&self.memory, value, self.lnb)? {
+                    Cll::Int(Some(val)) => val,
+                    Cll::Flt(_) => return Err(Error::CannotApplyBitwiseOnFlt(self.lnb)),
+                    _ => return Err(Error::CannotApplyOperationsOnChar(self.lnb)),
+                };
+                self.memory
+                    .set_cell(crate::CARRY_SLOT, Cll::Int(Some(!val)));
+                self.lnb + 1
+            }
+
+            // ! ------- `CEQ` -------------
+            // `ceq` instruction
+            Instruction::Ceq { op1, op2 } => {
+                let holds = Self::compare(&self.memory, op1, op2, Comparison::Eq, self.lnb)?;
+                self.memory
+                    .set_cell(crate::CARRY_SLOT, Cll::Int(Some(holds as i32)));
+                self.lnb + 1
+            }
+
+            // ! ------- `CNE` -------------
+            // `cne` instruction
+            Instruction::Cne { op1, op2 } => {
+                let holds = Self::compare(&self.memory, op1, op2, Comparison::Ne, self.lnb)?;
+                self.memory
+                    .set_cell(crate::CARRY_SLOT, Cll::Int(Some(holds as i32)));
+                self.lnb + 1
+            }
+
+            // ! ------- `CLT` -------------
+            // `clt` instruction
+            Instruction::Clt { op1, op2 } => {
+                let holds = Self::compare(&self.memory, op1, op2, Comparison::Lt, self.lnb)?;
+                self.memory
+                    .set_cell(crate::CARRY_SLOT, Cll::Int(Some(holds as i32)));
+                self.lnb + 1
+            }
+
+            // ! ------- `CLE` -------------
+            // `cle` instruction
+            Instruction::Cle { op1, op2 } => {
+                let holds = Self::compare(&self.memory, op1, op2, Comparison::Le, self.lnb)?;
+                self.memory
+                    .set_cell(crate::CARRY_SLOT, Cll::Int(Some(holds as i32)));
+                self.lnb + 1
+            }
+
+            // ! ------- `CGT` -------------
+            // `cgt` instruction
+            Instruction::Cgt { op1, op2 } => {
+                let holds = Self::compare(&self.memory, op1, op2, Comparison::Gt, self.lnb)?;
+                self.memory
+                    .set_cell(crate::CARRY_SLOT, Cll::Int(Some(holds as i32)));
+                self.lnb + 1
+            }
+
+            // ! ------- `CGE` -------------
+            // `cge` instruction
+            Instruction::Cge { op1, op2 } => {
+                let holds = Self::compare(&self.memory, op1, op2, Comparison::Ge, self.lnb)?;
+                self.memory
+                    .set_cell(crate::CARRY_SLOT, Cll::Int(Some(holds as i32)));
+                self.lnb + 1
+            }
+
+            // ! ------- `PRT` -------------
+            // `prt` instruction
+            Instruction::Prt { value } => {
+                let printed = match value {
+                    Val::Const(val) => self.output.print(self.lnb, None, &val.to_cll()),
+                    Val::Var(var) => match self.memory.cell(var.slot()) {
+                        Some(cll) => self.output.print(self.lnb, Some(&var.name), cll),
+                        None => {
+                            return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb))
+                        }
+                    },
+                };
+                if let Err(e) = printed {
+                    return Err(Error::CouldNotWriteOutput(e.kind(), self.lnb));
                 }
-                // ! ------- `MUL` -------------
-                // `mul` instruction
-                Instruction::Mul { var, value } => {
-                    crate::get_and_change!(self, var, value, |a, b| { a * b });
-                    self.lnb + 1
+                self.lnb + 1
+            }
+
+            // ! ------- `INP` -------------
+            // `inp` instruction
+            Instruction::Inp { var } => {
+                let mut text = String::new();
+                match self.input.read_line(&mut text) {
+                    Ok(0) => return Err(Error::EndOfInput(self.lnb)),
+                    Ok(_) => (),
+                    Err(e) => return Err(Error::CouldNotReadInput(e.kind(), self.lnb)),
+                }
+                let text = text.trim_end_matches(&['\n', '\r'][..]);
+                match self.memory.cell_mut(var.slot()) {
+                    Some(Cll::Int(cll_val)) => match text.trim().parse() {
+                        Ok(val) => *cll_val = Some(val),
+                        Err(_) => {
+                            return Err(Error::CouldNotParseIntValue(text.to_owned(), self.lnb))
+                        }
+                    },
+                    Some(Cll::Flt(cll_val)) => match text.trim().parse() {
+                        Ok(val) => *cll_val = Some(val),
+                        Err(_) => {
+                            return Err(Error::CouldNotParseFltValue(text.to_owned(), self.lnb))
+                        }
+                    },
+                    Some(Cll::Chr(cll_val)) => match text.parse() {
+                        Ok(val) => *cll_val = Some(val),
+                        Err(_) => {
+                            return Err(Error::CouldNotParseChrValue(text.to_owned(), self.lnb))
+                        }
+                    },
+                    None => return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb)),
                 }
+                self.lnb + 1
+            }
 
-                // ! ------- `DIV` -------------
-                // `div` instruction
-                Instruction::Div { var, value } => {
-                    crate::get_and_change!(self, var, value, |a, b| { a / b });
-                    self.lnb + 1
+            // ! ------- `PUSH` -------------
+            // `push` instruction
+            Instruction::Push { value } => {
+                if self.stack.len() >= self.max_stack_depth {
+                    return Err(Error::StackOverflow(self.max_stack_depth, self.lnb));
                 }
+                let cll = Self::value_of(&self.memory, value, self.lnb)?;
+                self.stack.push(cll);
+                self.lnb + 1
+            }
 
-                // ! ------- `MOD` -------------
-                // `mod` instruction
-                Instruction::Mod { var, value } => {
-                    crate::get_and_change!(self, var, value, |a, b| { a % b });
-                    self.lnb + 1
+            // ! ------- `POP` -------------
+            // `pop` instruction
+            Instruction::Pop { var } => {
+                let top = match self.stack.last() {
+                    Some(top) => top,
+                    None => return Err(Error::StackUnderflow(self.lnb)),
+                };
+                match (self.memory.cell_mut(var.slot()), top) {
+                    (Some(Cll::Int(cll_val)), Cll::Int(val)) => *cll_val = *val,
+                    (Some(Cll::Flt(cll_val)), Cll::Flt(val)) => *cll_val = *val,
+                    (Some(Cll::Chr(cll_val)), Cll::Chr(val)) => *cll_val = *val,
+                    (Some(_), _) => return Err(Error::VariablesDifferInType(self.lnb)),
+                    (None, _) => {
+                        return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb))
+                    }
+                }
+                self.stack.pop();
+                self.lnb + 1
+            }
+
+            // ! ------- `GTO` -------------
+            // `gto` instruction
+            Instruction::Gto { flag } => {
+                self.jumped = true;
+                flag.line()
+            }
+
+            // ! ------- `CAL` -------------
+            // `cal` instruction
+            Instruction::Cal { flag } => {
+                if self.calls.len() >= self.max_call_depth {
+                    return Err(Error::CallStackOverflow(self.max_call_depth, self.lnb));
+                }
+                self.calls.push(self.lnb + 1);
+                self.jumped = true;
+                flag.line()
+            }
+
+            // ! ------- `RET` -------------
+            // `ret` instruction
+            Instruction::Ret => match self.calls.pop() {
+                Some(line) => {
+                    self.jumped = true;
+                    line
                 }
+                None => return Err(Error::ReturnOutsideCall(self.lnb)),
+            },
 
-                // ! ------- `CADD` -------------
-                // `cadd` instruction
-                Instruction::Cadd { op1, op2 } => {
-                    crate::get_and_set_carry!(self, op1, op2, |a, b| { a + b });
+            // ! ------- `JMP` -------------
+            // `jmp` instruction
+            Instruction::Jmp { var, flag } => match self.memory.cell(var.slot()) {
+                Some(cll) => match cll {
+                    Cll::Int(Some(val)) => {
+                        crate::compare_and_get_flag!(self, val, flag, |&a| { a == 0 })
+                    }
+                    Cll::Flt(Some(val)) => {
+                        crate::compare_and_get_flag!(self, val, flag, |&a| { a == 0. })
+                    }
+                    Cll::Chr(_) => return Err(Error::CannotApplyComparisonsOnChar(self.lnb)),
+                    _ => return Err(Error::VariableIsUninitialized(var.to_string(), self.lnb)),
+                },
+                None => return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb)),
+            },
+
+            // ! ------- `JNE` -------------
+            // `jne` instruction
+            Instruction::Jne { var, flag } => match self.memory.cell(var.slot()) {
+                Some(cll) => match cll {
+                    Cll::Int(Some(val)) => {
+                        crate::compare_and_get_flag!(self, val, flag, |&a| { a != 0 })
+                    }
+                    Cll::Flt(Some(val)) => {
+                        crate::compare_and_get_flag!(self, val, flag, |&a| { a != 0. })
+                    }
+                    Cll::Chr(_) => return Err(Error::CannotApplyComparisonsOnChar(self.lnb)),
+                    _ => return Err(Error::VariableIsUninitialized(var.to_string(), self.lnb)),
+                },
+                None => return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb)),
+            },
+
+            // ! ------- `JEQ` -------------
+            // `jeq` instruction
+            Instruction::Jeq { op1, op2, flag } => {
+                if Self::compare(&self.memory, op1, op2, Comparison::Eq, self.lnb)? {
+                    self.jumped = true;
+                    flag.line()
+                } else {
                     self.lnb + 1
                 }
+            }
 
-                // ! ------- `CSUB` -------------
-                // `csub` instruction
-                Instruction::Csub { op1, op2 } => {
-                    crate::get_and_set_carry!(self, op1, op2, |a, b| { a - b });
+            // ! ------- `JNEQ` -------------
+            // `jne` instruction, comparing two operands
+            Instruction::Jneq { op1, op2, flag } => {
+                if Self::compare(&self.memory, op1, op2, Comparison::Ne, self.lnb)? {
+                    self.jumped = true;
+                    flag.line()
+                } else {
                     self.lnb + 1
                 }
+            }
 
-                // ! ------- `CMUL` -------------
-                // `cmul` instruction
-                Instruction::Cmul { op1, op2 } => {
-                    crate::get_and_set_carry!(self, op1, op2, |a, b| { a * b });
+            // ! ------- `JLT` -------------
+            // `jlt` instruction
+            Instruction::Jlt { op1, op2, flag } => {
+                if Self::compare(&self.memory, op1, op2, Comparison::Lt, self.lnb)? {
+                    self.jumped = true;
+                    flag.line()
+                } else {
                     self.lnb + 1
                 }
+            }
 
-                // ! ------- `CDIV` -------------
-                // `cdiv` instruction
-                Instruction::Cdiv { op1, op2 } => {
-                    crate::get_and_set_carry!(self, op1, op2, |a, b| { a / b });
+            // ! ------- `JLE` -------------
+            // `jle` instruction
+            Instruction::Jle { op1, op2, flag } => {
+                if Self::compare(&self.memory, op1, op2, Comparison::Le, self.lnb)? {
+                    self.jumped = true;
+                    flag.line()
+                } else {
                     self.lnb + 1
                 }
+            }
 
-                // ! ------- `CMOD` -------------
-                // `cmod` instruction
-                Instruction::Cmod { op1, op2 } => {
-                    crate::get_and_set_carry!(self, op1, op2, |a, b| { a % b });
+            // ! ------- `JGT` -------------
+            // `jgt` instruction
+            Instruction::Jgt { op1, op2, flag } => {
+                if Self::compare(&self.memory, op1, op2, Comparison::Gt, self.lnb)? {
+                    self.jumped = true;
+                    flag.line()
+                } else {
                     self.lnb + 1
                 }
+            }
 
-                // ! ------- `PRT` -------------
-                // `prt` instruction
-                Instruction::Prt { value } => {
-                    match value {
-                        Val::Value(val) => println!("l°{:2} : value =   {}", self.lnb, val),
-                        Val::Var(name) => match self.memory.get(name) {
-                            Some(val) => println!("l°{:2} : {:5} =   {}", self.lnb, name, val),
-                            None => {
-                                return Err(Error::VariableDoesNotExists(
-                                    name.to_string(),
-                                    self.lnb,
-                                ))
-                            }
-                        },
-                    }
+            // ! ------- `JGE` -------------
+            // `jge` instruction
+            Instruction::Jge { op1, op2, flag } => {
+                if Self::compare(&self.memory, op1, op2, Comparison::Ge, self.lnb)? {
+                    self.jumped = true;
+                    flag.line()
+                } else {
                     self.lnb + 1
                 }
+            }
 
-                // ! ------- `GTO` -------------
-                // `gto` instruction
-                Instruction::Gto { flag } => match self.file.flags.get(flag) {
-                    Some(line) => *line,
-                    None => return Err(Error::CouldNotFindFlag(flag.to_string(), self.lnb)),
-                },
+            // ! ------- `PAS` -------------
+            // `pas` instruction
+            Instruction::Pas => {
+                self.lnb += 1;
+                return Ok(Some(State::Paused(self.lnb - 1)));
+            }
 
-                // ! ------- `JMP` -------------
-                // `jmp` instruction
-                Instruction::Jmp { var, flag } => match self.memory.get(var) {
-                    Some(cll) => match cll {
-                        Cll::Int(Some(val)) => {
-                            crate::compare_and_get_flag!(self, val, flag, |&a| { a == 0 })
-                        }
-                        Cll::Flt(Some(val)) => {
-                            crate::compare_and_get_flag!(self, val, flag, |&a| { a == 0. })
-                        }
-                        Cll::Chr(_) => return Err(Error::CannotApplyComparisonsOnChar(self.lnb)),
-                        _ => return Err(Error::VariableIsUninitialized(var.to_string(), self.lnb)),
-                    },
-                    None => return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb)),
-                },
+            // ! ------- `YLD` -------------
+            // `yld` instruction
+            Instruction::Yld { value } => {
+                let cll = Self::value_of(&self.memory, value, self.lnb)?;
+                self.lnb += 1;
+                return Ok(Some(State::Yielded(cll)));
+            }
 
-                // ! ------- `JNE` -------------
-                // `jne` instruction
-                Instruction::Jne { var, flag } => match self.memory.get(var) {
-                    Some(cll) => match cll {
-                        Cll::Int(Some(val)) => {
-                            crate::compare_and_get_flag!(self, val, flag, |&a| { a != 0 })
-                        }
-                        Cll::Flt(Some(val)) => {
-                            crate::compare_and_get_flag!(self, val, flag, |&a| { a != 0. })
-                        }
-                        Cll::Chr(_) => return Err(Error::CannotApplyComparisonsOnChar(self.lnb)),
-                        _ => return Err(Error::VariableIsUninitialized(var.to_string(), self.lnb)),
-                    },
-                    None => return Err(Error::VariableDoesNotExists(var.to_string(), self.lnb)),
-                },
+            // ! ------- `FLG` -------------
+            // `flg` instruction
+            Instruction::Flg => self.lnb + 1,
 
-                // ! ------- `FLG` -------------
-                // `flg` instruction
-                Instruction::Flg => self.lnb + 1,
+            // ! ------- `NLL` -------------
+            // `nll` instruction
+            Instruction::Nll => self.lnb + 1,
 
-                // ! ------- `NLL` -------------
-                // `nll` instruction
-                Instruction::Nll => self.lnb + 1,
+            // ! ------- `ERR` -------------
+            // Instruction is not implemented yet
+            e => {
+                return Err(Error::UnimplementedInstruction(
+                    Box::new((*e).clone()),
+                    self.lnb,
+                ))
+            }
+        };
+        Ok(None)
+    }
 
-                // ! ------- `ERR` -------------
-                // Instruction is not implemented yet
-                e => return Err(Error::UnimplementedInstruction((*e).clone(), self.lnb)),
+    /// Resumes a program that was paused or yielded a value, keeping its memory and stacks
+    pub fn resume(&mut self) -> Result<State, Error> {
+        self.run()
+    }
+
+    /// Compares two operands of the same type, a hardcoded value taking the type of the
+    /// variable it is compared to
+    fn compare(
+        memory: &Mem,
+        op1: &Val,
+        op2: &Val,
+        comparison: Comparison,
+        lnb: usize,
+    ) -> Result<bool, Error> {
+        let (a, b) = match (op1, op2) {
+            (Val::Const(_), Val::Const(_)) => return Err(Error::CannotDetermineReturnType(lnb)),
+            (Val::Var(_), Val::Const(val)) => {
+                let a = Self::value_of(memory, op1, lnb)?;
+                let b = Self::coerce(*val, &a, lnb)?;
+                (a, b)
+            }
+            (Val::Const(val), Val::Var(_)) => {
+                let b = Self::value_of(memory, op2, lnb)?;
+                let a = Self::coerce(*val, &b, lnb)?;
+                (a, b)
             }
+            (Val::Var(_), Val::Var(_)) => (
+                Self::value_of(memory, op1, lnb)?,
+                Self::value_of(memory, op2, lnb)?,
+            ),
+        };
+        match (a, b) {
+            (Cll::Int(Some(a)), Cll::Int(Some(b))) => Ok(comparison.holds(a, b)),
+            (Cll::Flt(Some(a)), Cll::Flt(Some(b))) => Ok(comparison.holds(a, b)),
+            (Cll::Chr(Some(a)), Cll::Chr(Some(b))) => Ok(comparison.holds(a, b)),
+            _ => Err(Error::VariablesDifferInType(lnb)),
+        }
+    }
+
+    /// Returns a hardcoded value as a value of the same type as `cll`
+    fn coerce(val: Const, cll: &Cll, lnb: usize) -> Result<Cll, Error> {
+        let coerced = match cll {
+            Cll::Int(_) => val.as_int().map(|val| Cll::Int(Some(val))),
+            Cll::Flt(_) => val.as_flt().map(|val| Cll::Flt(Some(val))),
+            Cll::Chr(_) => val.as_chr().map(|val| Cll::Chr(Some(val))),
+        };
+        coerced.ok_or(Error::CannotDetermineReturnType(lnb))
+    }
+
+    /// Returns the value of an operand, a hardcoded value being read as the given type
+    fn value_as(memory: &Mem, value: &Val, var_type: Type, lnb: usize) -> Result<Cll, Error> {
+        let val = match value {
+            Val::Const(val) => val,
+            Val::Var(_) => return Self::value_of(memory, value, lnb),
+        };
+        let converted = match var_type {
+            Type::Int => val.as_int().map(|val| Cll::Int(Some(val))),
+            Type::Flt => val.as_flt().map(|val| Cll::Flt(Some(val))),
+            Type::Chr => val.as_chr().map(|val| Cll::Chr(Some(val))),
+        };
+        converted.ok_or_else(|| Error::could_not_parse(var_type, val, lnb))
+    }
+
+    /// Sets a value into an existing variable of the same type
+    fn store(memory: &mut Mem, var: &Var, cll: Cll, lnb: usize) -> Result<(), Error> {
+        match (memory.cell_mut(var.slot()), cll) {
+            (Some(Cll::Int(cll_val)), Cll::Int(val)) => *cll_val = val,
+            (Some(Cll::Flt(cll_val)), Cll::Flt(val)) => *cll_val = val,
+            (Some(Cll::Chr(cll_val)), Cll::Chr(val)) => *cll_val = val,
+            (Some(_), _) => return Err(Error::VariablesDifferInType(lnb)),
+            (None, _) => return Err(Error::VariableDoesNotExists(var.to_string(), lnb)),
         }
-        Ok(self.lnb)
+        Ok(())
     }
+
+    /// Returns the value of a variable or a hardcoded value
+    fn value_of(memory: &Mem, value: &Val, lnb: usize) -> Result<Cll, Error> {
+        match value {
+            Val::Const(val) => Ok(val.to_cll()),
+            Val::Var(var) => match memory.cell(var.slot()) {
+                Some(Cll::Int(None)) | Some(Cll::Flt(None)) | Some(Cll::Chr(None)) => {
+                    Err(Error::VariableIsUninitialized(var.to_string(), lnb))
+                }
+                Some(cll) => Ok(cll.clone()),
+                None => Err(Error::VariableDoesNotExists(var.to_string(), lnb)),
+            },
+        }
+    }
+}
+
+/// State of a program when it hands control back to the host
+#[derive(Debug, PartialEq, Clone)]
+pub enum State {
+    /// The program reached its end at the given line
+    Finished(usize),
+    /// The program executed `pas` at the given line, and can be resumed
+    Paused(usize),
+    /// The program executed `yld` with the given value, and can be resumed
+    Yielded(Cll),
+}
+
+/// What happened during the execution of a single instruction
+#[derive(Debug, PartialEq, Clone)]
+pub struct Step {
+    /// Line of the executed instruction
+    pub line: usize,
+    /// Line of the next instruction to execute
+    pub next: usize,
+    /// Variables written, created or deleted by the instruction
+    pub written: Vec<String>,
+    /// Line jumped to by a taken jump, a call or a return, even if it is the following line
+    pub jump: Option<usize>,
+    /// Set if the program paused, yielded a value or reached its end
+    pub state: Option<State>,
+}
+
+/// Observer called around the execution of each instruction, e.g. to trace a program
+pub trait Hook {
+    /// Called before the instruction at `line` is executed
+    fn before(&mut self, _program: &Program, _line: usize, _instruction: &Instruction) {}
+    /// Called after an instruction was successfully executed
+    fn after(&mut self, _program: &Program, _step: &Step) {}
 }
 
 /// Contains runtime type of errors
 #[derive(Debug)]
 pub enum Error {
-    UnimplementedInstruction(Instruction, usize),
+    UnimplementedInstruction(Box<Instruction>, usize),
     VariableDoesNotExists(String, usize),
     VariablesDifferInType(usize),
     VariableIsUninitialized(String, usize),
-    CouldNotParseIntValue(String),
-    CouldNotParseFltValue(String),
-    CouldNotParseChrValue(String),
+    CouldNotParseIntValue(String, usize),
+    CouldNotParseFltValue(String, usize),
+    CouldNotParseChrValue(String, usize),
     CannotApplyOperationsOnChar(usize),
     CannotApplyComparisonsOnChar(usize),
     CouldNotFindFlag(String, usize),
     CannotDetermineReturnType(usize),
+    CouldNotWriteOutput(io::ErrorKind, usize),
+    CouldNotReadInput(io::ErrorKind, usize),
+    EndOfInput(usize),
+    StackUnderflow(usize),
+    StackOverflow(usize, usize),
+    CallStackOverflow(usize, usize),
+    ReturnOutsideCall(usize),
+    FuelExhausted {
+        executed: u64,
+        line: usize,
+    },
+    DeadlineExceeded {
+        executed: u64,
+        line: usize,
+    },
+    IntegerOverflow(usize),
+    DivisionByZero(usize),
+    ModuloByZero(usize),
+    CannotApplyBitwiseOnFlt(usize),
+    /// Shift by an amount outside of `0..32` : amount, line
+    ShiftOutOfRange(i32, usize),
+    /// A `flt` converted by `fti` that is NaN, infinite or out of the bounds of `int`
+    CannotConvertToInt(f64, usize),
+    InvalidCodePoint(i32, usize),
+}
+
+impl Error {
+    /// Returns the error raised when a hardcoded value does not fit a variable of the given type
+    pub(crate) fn could_not_parse(var_type: Type, val: &Const, line: usize) -> Self {
+        match var_type {
+            Type::Int => Error::CouldNotParseIntValue(val.to_string(), line),
+            Type::Flt => Error::CouldNotParseFltValue(val.to_string(), line),
+            Type::Chr => Error::CouldNotParseChrValue(val.to_string(), line),
+        }
+    }
+
+    /// Returns the line the error happened at.
+    pub fn line(&self) -> usize {
+        match self {
+            Error::UnimplementedInstruction(_, line)
+            | Error::VariableDoesNotExists(_, line)
+            | Error::VariablesDifferInType(line)
+            | Error::VariableIsUninitialized(_, line)
+            | Error::CouldNotParseIntValue(_, line)
+            | Error::CouldNotParseFltValue(_, line)
+            | Error::CouldNotParseChrValue(_, line)
+            | Error::CannotApplyOperationsOnChar(line)
+            | Error::CannotApplyComparisonsOnChar(line)
+            | Error::CouldNotFindFlag(_, line)
+            | Error::CannotDetermineReturnType(line)
+            | Error::CouldNotWriteOutput(_, line)
+            | Error::CouldNotReadInput(_, line)
+            | Error::EndOfInput(line)
+            | Error::StackUnderflow(line)
+            | Error::StackOverflow(_, line)
+            | Error::CallStackOverflow(_, line)
+            | Error::ReturnOutsideCall(line)
+            | Error::FuelExhausted { line, .. }
+            | Error::DeadlineExceeded { line, .. }
+            | Error::IntegerOverflow(line)
+            | Error::DivisionByZero(line)
+            | Error::ModuloByZero(line)
+            | Error::CannotApplyBitwiseOnFlt(line)
+            | Error::ShiftOutOfRange(_, line)
+            | Error::CannotConvertToInt(_, line)
+            | Error::InvalidCodePoint(_, line) => *line,
+        }
+    }
 }
+
+impl Located for Error {
+    fn code(&self) -> &'static str {
+        match self {
+            Error::UnimplementedInstruction(..) => "E0101",
+            Error::VariableDoesNotExists(..) => "E0102",
+            Error::VariablesDifferInType(_) => "E0103",
+            Error::VariableIsUninitialized(..) => "E0104",
+            Error::CouldNotParseIntValue(..) => "E0105",
+            Error::CouldNotParseFltValue(..) => "E0106",
+            Error::CouldNotParseChrValue(..) => "E0107",
+            Error::CannotApplyOperationsOnChar(_) => "E0108",
+            Error::CannotApplyComparisonsOnChar(_) => "E0109",
+            Error::CouldNotFindFlag(..) => "E0110",
+            Error::CannotDetermineReturnType(_) => "E0111",
+            Error::CouldNotWriteOutput(..) => "E0112",
+            Error::CouldNotReadInput(..) => "E0113",
+            Error::EndOfInput(_) => "E0114",
+            Error::StackUnderflow(_) => "E0115",
+            Error::StackOverflow(..) => "E0116",
+            Error::CallStackOverflow(..) => "E0117",
+            Error::ReturnOutsideCall(_) => "E0118",
+            Error::FuelExhausted { .. } => "E0119",
+            Error::DeadlineExceeded { .. } => "E0120",
+            Error::IntegerOverflow(_) => "E0121",
+            Error::DivisionByZero(_) => "E0122",
+            Error::ModuloByZero(_) => "E0123",
+            Error::CannotApplyBitwiseOnFlt(_) => "E0124",
+            Error::ShiftOutOfRange(..) => "E0125",
+            Error::CannotConvertToInt(..) => "E0126",
+            Error::InvalidCodePoint(..) => "E0127",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Error::UnimplementedInstruction(ins, _) => {
+                format!("instruction `{:?}` is not implemented yet", ins)
+            }
+            Error::VariableDoesNotExists(name, _) => format!("variable `{}` does not exist", name),
+            Error::VariablesDifferInType(_) => "operands differ in type".to_owned(),
+            Error::VariableIsUninitialized(name, _) => {
+                format!("variable `{}` is uninitialized", name)
+            }
+            Error::CouldNotParseIntValue(val, _) => {
+                format!("could not parse `{}` as an `int`", val)
+            }
+            Error::CouldNotParseFltValue(val, _) => format!("could not parse `{}` as a `flt`", val),
+            Error::CouldNotParseChrValue(val, _) => format!("could not parse `{}` as a `chr`", val),
+            Error::CannotApplyOperationsOnChar(_) => {
+                "cannot apply arithmetic operations on `chr`".to_owned()
+            }
+            Error::CannotApplyComparisonsOnChar(_) => {
+                "cannot apply comparisons on `chr`".to_owned()
+            }
+            Error::CouldNotFindFlag(flag, _) => format!("could not find flag `{}`", flag),
+            Error::CannotDetermineReturnType(_) => {
+                "cannot determine the type of the result".to_owned()
+            }
+            Error::CouldNotWriteOutput(kind, _) => format!("could not write output : {}", kind),
+            Error::CouldNotReadInput(kind, _) => format!("could not read input : {}", kind),
+            Error::EndOfInput(_) => "no more input to read".to_owned(),
+            Error::StackUnderflow(_) => "cannot pop from an empty stack".to_owned(),
+            Error::StackOverflow(depth, _) => {
+                format!("stack overflow : it cannot hold more than {} values", depth)
+            }
+            Error::CallStackOverflow(depth, _) => {
+                format!("call stack overflow : more than {} nested calls", depth)
+            }
+            Error::ReturnOutsideCall(_) => "`ret` outside of a subroutine call".to_owned(),
+            Error::FuelExhausted { executed, .. } => {
+                format!("fuel exhausted after {} instructions", executed)
+            }
+            Error::DeadlineExceeded { executed, .. } => {
+                format!("deadline exceeded after {} instructions", executed)
+            }
+            Error::IntegerOverflow(_) => "integer overflow".to_owned(),
+            Error::DivisionByZero(_) => "division by zero".to_owned(),
+            Error::ModuloByZero(_) => "modulo by zero".to_owned(),
+            Error::CannotApplyBitwiseOnFlt(_) => {
+                "cannot apply bitwise operations on `flt`".to_owned()
+            }
+            Error::ShiftOutOfRange(amount, _) => {
+                format!("cannot shift by {} bits, expected 0 to 31", amount)
+            }
+            Error::CannotConvertToInt(val, _) => {
+                format!("cannot convert `{}` to an `int`", val)
+            }
+            Error::InvalidCodePoint(val, _) => format!("`{}` is not a valid code point", val),
+        }
+    }
+
+    fn location(&self) -> Option<(usize, Highlight)> {
+        let highlight = match self {
+            Error::UnimplementedInstruction(..) => Highlight::Instruction,
+            Error::VariableDoesNotExists(name, _)
+            | Error::VariableIsUninitialized(name, _)
+            | Error::CouldNotParseIntValue(name, _)
+            | Error::CouldNotParseFltValue(name, _)
+            | Error::CouldNotParseChrValue(name, _)
+            | Error::CouldNotFindFlag(name, _) => Highlight::Text(name.clone()),
+            Error::CannotApplyComparisonsOnChar(_) => Highlight::Operand(0),
+            _ => Highlight::Line,
+        };
+        Some((self.line(), highlight))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}", self.message(), self.line() + 1)
+    }
+}
+
+impl std::error::Error for Error {}
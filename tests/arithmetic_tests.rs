@@ -0,0 +1,47 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+
+const OVERFLOW: &str = "var: &a, int\nset: &a, 2147483600\nadd: &a, 100\ncmul: &a, 2\n";
+
+#[test]
+fn checked_arithmetic() {
+    match program(OVERFLOW).run() {
+        Err(Error::IntegerOverflow(2)) => (),
+        e => panic!("Expected overflow, got : {:?}", e),
+    }
+    match program("var: &a, int\nset: &a, 2147483647\ninc: &a\n").run() {
+        Err(Error::IntegerOverflow(2)) => (),
+        e => panic!("Expected overflow, got : {:?}", e),
+    }
+    match program("var: &a, int\nset: &a, 5\ndiv: &a, 0\n").run() {
+        Err(Error::DivisionByZero(2)) => (),
+        e => panic!("Expected division by zero, got : {:?}", e),
+    }
+    match program("var: &a, int\nset: &a, 0\ncmod: 5, &a\n").run() {
+        Err(Error::ModuloByZero(2)) => (),
+        e => panic!("Expected modulo by zero, got : {:?}", e),
+    }
+    let prog = run("var: &a, flt\nset: &a, 5\ndiv: &a, 0\n");
+    assert_eq!(prog.memory["&a"], Cll::Flt(Some(f64::INFINITY)));
+}
+
+#[test]
+fn wrapping_and_saturating_arithmetic() {
+    let prog = finish(program(OVERFLOW).with_arithmetic(Arithmetic::Wrapping));
+    assert_eq!(prog.memory["&a"], Cll::Int(Some(-2147483596)));
+    assert_eq!(prog.memory["-"], Cll::Int(Some(104)));
+
+    let prog = finish(program(OVERFLOW).with_arithmetic(Arithmetic::Saturating));
+    assert_eq!(prog.memory["&a"], Cll::Int(Some(i32::MAX)));
+    assert_eq!(prog.memory["-"], Cll::Int(Some(i32::MAX)));
+
+    match program("var: &a, int\nset: &a, 5\nmod: &a, 0\n")
+        .with_arithmetic(Arithmetic::Wrapping)
+        .run()
+    {
+        Err(Error::ModuloByZero(2)) => (),
+        e => panic!("Expected modulo by zero, got : {:?}", e),
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+
+#[test]
+fn bitwise_operations() {
+    let prog = run(
+        "var: &a, int\nset: &a, 12\nand: &a, 10\nvar: &b, int\nset: &b, 12\nor: &b, 3\n\
+         var: &c, int\nset: &c, 12\nxor: &c, &b\nvar: &d, int\nset: &d, -16\nshr: &d, 2\n\
+         var: &e, int\nset: &e, 1\nshl: &e, 31\nnot: &a\n",
+    );
+    assert_eq!(prog.memory["&a"], Cll::Int(Some(!8)));
+    assert_eq!(prog.memory["&b"], Cll::Int(Some(15)));
+    assert_eq!(prog.memory["&c"], Cll::Int(Some(3)));
+    assert_eq!(prog.memory["&d"], Cll::Int(Some(-4)));
+    assert_eq!(prog.memory["&e"], Cll::Int(Some(i32::MIN)));
+}
+
+#[test]
+fn carry_bitwise_operations() {
+    let prog = run(
+        "var: &a, int\nset: &a, 6\ncand: &a, 3\nvar: &r, int\nset: &r, -\ncxor: 5, &a\n\
+         var: &x, int\nset: &x, -\ncshl: &a, 4\nvar: &y, int\nset: &y, -\ncnot: 0\n",
+    );
+    assert_eq!(prog.memory["&r"], Cll::Int(Some(2)));
+    assert_eq!(prog.memory["&x"], Cll::Int(Some(3)));
+    assert_eq!(prog.memory["&y"], Cll::Int(Some(96)));
+    assert_eq!(prog.memory["&a"], Cll::Int(Some(6)));
+    assert_eq!(prog.memory["-"], Cll::Int(Some(-1)));
+}
+
+#[test]
+fn bitwise_errors() {
+    match program("var: &a, int\nset: &a, 1\nshl: &a, 32\n").run() {
+        Err(Error::ShiftOutOfRange(32, 2)) => (),
+        e => panic!("Expected shift out of range, got : {:?}", e),
+    }
+    match program("var: &a, int\nset: &a, 1\ncshr: &a, -1\n").run() {
+        Err(Error::ShiftOutOfRange(-1, 2)) => (),
+        e => panic!("Expected shift out of range, got : {:?}", e),
+    }
+    match program("var: &f, flt\nset: &f, 1.5\nand: &f, 1\n").run() {
+        Err(Error::CannotApplyBitwiseOnFlt(2)) => (),
+        e => panic!("Expected bitwise on flt error, got : {:?}", e),
+    }
+    match program("var: &f, flt\nset: &f, 1.5\ncor: 2, &f\n").run() {
+        Err(Error::CannotApplyBitwiseOnFlt(2)) => (),
+        e => panic!("Expected bitwise on flt error, got : {:?}", e),
+    }
+    match program("var: &f, flt\nset: &f, 1.5\nnot: &f\n").run() {
+        Err(Error::CannotApplyBitwiseOnFlt(2)) => (),
+        e => panic!("Expected bitwise on flt error, got : {:?}", e),
+    }
+    match program("var: &c, chr\nset: &c, a\nxor: &c, 1\n").run() {
+        Err(Error::CannotApplyOperationsOnChar(2)) => (),
+        e => panic!("Expected operation on chr error, got : {:?}", e),
+    }
+    assert_eq!(
+        check_errors(
+            "var: &f, flt\nand: &f, 1\nvar: &c, chr\nnot: &c\ncshl: &f, 1\nvar: &i, int\nset: &i, -\ncnot: 1.5\n"
+        ),
+        vec![("E0124", 1), ("E0108", 3), ("E0124", 4), ("E0103", 6), ("E0124", 7)]
+    );
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::*;
+
+#[test]
+fn valid_programs() {
+    for name in [
+        "basic",
+        "tables",
+        "input",
+        "stack",
+        "subroutines",
+        "generator",
+        "import",
+    ] {
+        let prog_file = open(&format!("tests/test_programs/{}.moon", name));
+        if let Err(e) = prog_file.check() {
+            panic!("Error during checking {} : {:?}", name, e)
+        }
+    }
+}
+
+#[test]
+fn type_mismatches() {
+    assert_eq!(
+        check_errors("var: &a, int\nvar: &b, flt\nadd: &a, &b\nset: &b, &a\nset: &a, 1.5\n"),
+        vec![("E0103", 2), ("E0103", 3), ("E0105", 4)]
+    );
+    // The carry variable takes the type of the operation
+    assert_eq!(
+        check_errors("var: &f, flt\ncadd: &f, 1\nvar: &i, int\nset: &i, -\ncmul: 2.5, &i\n"),
+        vec![("E0103", 3), ("E0111", 4)]
+    );
+    // Values on the stack keep their types
+    assert_eq!(
+        check_errors("var: &a, int\npush: 1.5\npush: 2\npop: &a\npop: &a\n"),
+        vec![("E0103", 4)]
+    );
+}
+
+#[test]
+fn undeclared_variables() {
+    assert_eq!(
+        check_errors("prt: &a\nvar: &a, int\ncadd: &a, -\nset: &a, 1\ndel: &a\ninc: &a\n"),
+        vec![("E0102", 0), ("E0102", 2), ("E0102", 5)]
+    );
+    // `&b` is declared only when the jump is not taken
+    assert_eq!(
+        check_errors("var: &a, int\nset: &a, 0\njmp: &a, end\nvar: &b, int\nflg: end\nprt: &b\n"),
+        vec![("E0102", 5)]
+    );
+    // Lines no path goes through are not checked
+    assert_eq!(check_errors("gto: end\nprt: &a\nflg: end\n"), vec![]);
+}
+
+#[test]
+fn operations_on_chr() {
+    assert_eq!(
+        check_errors(
+            "var: &c, chr\nset: &c, a\nadd: &c, 1\ninc: &c\ncsub: &c, 1\njne: &c, end\nflg: end\n"
+        ),
+        vec![("E0108", 2), ("E0108", 3), ("E0108", 4), ("E0109", 5)]
+    );
+    // `&v` is a `chr` on one of the paths leading to the loop
+    assert_eq!(
+        check_errors(
+            "var: &v, int\nset: &v, 1\nflg: loop\njmp: &v, loop\nvar: &v, chr\ngto: loop\n"
+        ),
+        vec![("E0109", 3)]
+    );
+}
+
+#[test]
+fn subroutines() {
+    // `&r` is declared by the subroutine, before returning
+    assert_eq!(
+        check_errors("cal: init\nprt: &r\ngto: end\nflg: init\nvar: &r, int\nret:\nflg: end\n"),
+        vec![]
+    );
+    assert_eq!(
+        check_errors("cal: init\nprt: &r\ngto: end\nflg: init\ndel: &r\nret:\nflg: end\n"),
+        vec![("E0102", 1), ("E0102", 4)]
+    );
+}
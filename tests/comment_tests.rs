@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use libmoon::instructions::*;
+use libmoon::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const SOURCE: &str = "# Prints characters : spaces, commas and colons
+var: &c, chr // the printed one
+
+set: &c, ' '
+prt: &c # a space
+set: &c, ','
+prt: &c
+prt: ':'
+prt: '\\''
+";
+
+#[test]
+fn comments_and_quotes() {
+    let prog_file = parse(SOURCE);
+    assert_eq!(prog_file.lines.len(), 9);
+    assert_eq!(prog_file.lines[0].instruction, Instruction::Nll);
+    assert_eq!(prog_file.lines[2].instruction, Instruction::Nll);
+    assert_eq!(
+        prog_file.lines[3].instruction,
+        Instruction::Set {
+            var: Var::new("&c"),
+            value: Val::Const(Const::Chr(' '))
+        }
+    );
+    assert_eq!(prog_file.lines[1].span.operands, vec![5..7, 9..12]);
+    assert_eq!(prog_file.lines[3].span.operands, vec![5..7, 9..12]);
+
+    let printed = Rc::new(RefCell::new(Vec::new()));
+    let sink = printed.clone();
+    finish(
+        compile(prog_file).with_output(move |_: usize, _: Option<&str>, value: &Cll| {
+            sink.borrow_mut().push(value.plain());
+            Ok(())
+        }),
+    );
+    assert_eq!(*printed.borrow(), vec![" ", ",", ":", "'"]);
+}
+
+#[test]
+fn quote_errors() {
+    let mut prog_file = ProgramFile::from_source("var: &c, chr\nset: &c, 'a\nset: &c, 'ab'\n");
+    let errors = match prog_file.parse_all() {
+        Ok(_) => panic!("Program should not parse"),
+        Err(e) => e,
+    };
+    match &errors[..] {
+        [ErrorIO::UnterminatedQuote(1), ErrorIO::InvalidValue(value, 2)] => {
+            assert_eq!(value, "'ab'")
+        }
+        e => panic!("Expected quote errors, got : {:?}", e),
+    }
+    assert_eq!(
+        prog_file.render(&errors[1]),
+        "error[E0013]: `'ab'` is neither an `int`, a `flt` nor a `chr`
+ --> <source>:3:10
+  |
+3 | set: &c, 'ab'
+  |          ^^^^
+"
+    );
+}
+
+#[test]
+fn apostrophes_in_plain_text_comments() {
+    let prog_file = parse("This program doesn't do much\nvar: &a, int # it's an int\nset: &a, 1\n");
+    assert_eq!(prog_file.lines[0].instruction, Instruction::Nll);
+    assert_eq!(
+        prog_file.lines[1].instruction,
+        Instruction::Var {
+            var: Var::new("&a"),
+            var_type: Type::Int
+        }
+    );
+}
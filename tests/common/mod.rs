@@ -0,0 +1,68 @@
+//! Fixtures shared by the integration tests, each test crate only uses some of them
+#![allow(dead_code)]
+
+use libmoon::diagnostics::Located;
+use libmoon::*;
+
+/// Parses a program written in a string
+pub fn parse(source: &str) -> ProgramFile {
+    let mut prog_file = ProgramFile::from_source(source);
+    match prog_file.parse() {
+        Ok(_) => println!("Program parsed !"),
+        Err(e) => panic!("Error during parsing : {:?}", e),
+    }
+    prog_file
+}
+
+/// Opens and parses a program file
+pub fn open(file_name: &str) -> ProgramFile {
+    let mut prog_file = match ProgramFile::open(file_name) {
+        Ok(f) => f,
+        Err(e) => panic!("Error during opening : {:?}", e),
+    };
+    match prog_file.parse() {
+        Ok(_) => println!("Program parsed !"),
+        Err(e) => panic!("Error during parsing : {:?}", e),
+    }
+    prog_file
+}
+
+/// Compiles a parsed program
+pub fn compile(prog_file: ProgramFile) -> Program {
+    match Program::compile(prog_file) {
+        Ok(prog) => prog,
+        Err(e) => panic!("Error during compilation : {:?}", e),
+    }
+}
+
+/// Parses and compiles a program written in a string
+pub fn program(source: &str) -> Program {
+    compile(parse(source))
+}
+
+/// Discards what the program prints
+pub fn quiet(prog: Program) -> Program {
+    prog.with_output(|_: usize, _: Option<&str>, _: &Cll| Ok(()))
+}
+
+/// Runs a program until it stops without error
+pub fn finish(mut prog: Program) -> Program {
+    match prog.run() {
+        Ok(s) => println!("Program finished successfuly : {:?}", s),
+        Err(e) => panic!("Error during runtime : {:?}", e),
+    };
+    prog
+}
+
+/// Parses, compiles and runs a program written in a string
+pub fn run(source: &str) -> Program {
+    finish(program(source))
+}
+
+/// Returns the code and line of each error found by the checker
+pub fn check_errors(source: &str) -> Vec<(&'static str, usize)> {
+    match parse(source).check() {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors.iter().map(|e| (e.code(), e.line())).collect(),
+    }
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::*;
+use libmoon::instructions::Arity;
+use libmoon::*;
+
+#[test]
+fn comparisons() {
+    let prog = run("var: &a, int\nset: &a, 3\nvar: &b, int\nset: &b, 5\nclt: &a, &b\n");
+    assert_eq!(prog.memory["-"], Cll::Int(Some(1)));
+    let prog = run("var: &a, int\nset: &a, 3\ncge: &a, 5\n");
+    assert_eq!(prog.memory["-"], Cll::Int(Some(0)));
+    // Hardcoded values take the type of the other operand
+    let prog = run("var: &f, flt\nset: &f, 2.5\ncgt: &f, 2\n");
+    assert_eq!(prog.memory["-"], Cll::Int(Some(1)));
+    // `chr` are compared by code point
+    let prog =
+        run("var: &c, chr\nset: &c, 'a'\ncle: &c, 'b'\nvar: &r, int\nset: &r, -\nceq: 'a', &c\n");
+    assert_eq!(prog.memory["&r"], Cll::Int(Some(1)));
+    assert_eq!(prog.memory["-"], Cll::Int(Some(1)));
+}
+
+#[test]
+fn conditional_jumps() {
+    // Counts from 0 to 10
+    let prog = run(
+        "var: &i, int\nset: &i, 0\nflg: loop\ninc: &i\njlt: &i, 10, loop\nvar: &n, int\nset: &n, 0\n\
+         jeq: &i, &n, end\njne: &i, 10, end\nset: &n, 1\nflg: end\n",
+    );
+    assert_eq!(prog.memory["&i"], Cll::Int(Some(10)));
+    assert_eq!(prog.memory["&n"], Cll::Int(Some(1)));
+    // `jne` with two operands still tests against zero
+    let prog = run("var: &a, int\nset: &a, 0\njne: &a, end\nset: &a, 1\nflg: end\n");
+    assert_eq!(prog.memory["&a"], Cll::Int(Some(1)));
+}
+
+#[test]
+fn comparison_errors() {
+    match program("var: &a, int\nset: &a, 1\nvar: &f, flt\nset: &f, 1\nceq: &a, &f\n").run() {
+        Err(Error::VariablesDifferInType(4)) => (),
+        e => panic!("Expected type error, got : {:?}", e),
+    }
+    match Program::compile(parse(
+        "var: &a, int\nset: &a, 1\njge: &a, 1.5, end\nflg: end\n",
+    )) {
+        Err(Error::CannotDetermineReturnType(2)) => (),
+        Err(e) => panic!("Expected return type error, got : {:?}", e),
+        Ok(_) => panic!("Program should not compile"),
+    }
+    match program("clt: 1, 2\n").run() {
+        Err(Error::CannotDetermineReturnType(0)) => (),
+        e => panic!("Expected return type error, got : {:?}", e),
+    }
+    match ProgramFile::from_source("var: &a, int\njlt: &a, end\n").parse() {
+        Err(ErrorIO::NotEnoughOperands(ins, Arity { min: 3, max: 3 }, 1)) => assert_eq!(ins, "jlt"),
+        e => panic!("Expected operand count error, got : {:?}", e),
+    }
+    match ProgramFile::from_source("var: &a, int\nset: &a, 1, 2\n").parse() {
+        Err(ErrorIO::TooMuchOperands(ins, Arity { min: 2, max: 2 }, 1)) => assert_eq!(ins, "set"),
+        e => panic!("Expected operand count error, got : {:?}", e),
+    }
+    match ProgramFile::from_source("var: &a, int\njge: &a, , end\nflg: end\n").parse() {
+        Err(ErrorIO::EmptyOperand(1, 2)) => (),
+        e => panic!("Expected empty operand error, got : {:?}", e),
+    }
+}
+
+#[test]
+fn checked_comparisons() {
+    assert_eq!(
+        check_errors(
+            "var: &a, int\nvar: &c, chr\nclt: &a, &c\njeq: &c, 'x', end\ncne: 1, 2\nvar: &b, int\nset: &b, -\nflg: end\n",
+        ),
+        vec![("E0103", 2), ("E0111", 4)]
+    );
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::*;
+use libmoon::instructions::*;
+use libmoon::*;
+
+#[test]
+fn resolved_code() {
+    let prog = program("var: &a, flt\nflg: loop\nadd: &a, 2\ncsub: &a, &b\njne: -, loop\n");
+    let code = prog.code();
+    assert_eq!(
+        code[2].instruction,
+        Instruction::Add {
+            var: Var {
+                name: "&a".to_owned(),
+                slot: Some(1)
+            },
+            value: Val::Const(Const::Int(2))
+        }
+    );
+    assert_eq!(
+        code[3].instruction,
+        Instruction::Csub {
+            op1: Val::Var(Var {
+                name: "&a".to_owned(),
+                slot: Some(1)
+            }),
+            op2: Val::Var(Var {
+                name: "&b".to_owned(),
+                slot: Some(2)
+            })
+        }
+    );
+    assert_eq!(
+        code[4].instruction,
+        Instruction::Jne {
+            var: Var {
+                name: "-".to_owned(),
+                slot: Some(CARRY_SLOT)
+            },
+            flag: Flag {
+                name: "loop".to_owned(),
+                line: Some(1)
+            }
+        }
+    );
+    assert_eq!(prog.memory.slot("&b"), Some(2));
+    assert!(!prog.memory.contains_key("&b"));
+}
+
+#[test]
+fn compile_errors() {
+    // Undefined flags are parse errors, but the program can still be compiled after `parse_all`
+    let mut prog_file = ProgramFile::from_source("var: &a, int\nset: &a, 1\ngto: nowhere\n");
+    if prog_file.parse_all().is_ok() {
+        panic!("Program should not parse")
+    }
+    match Program::compile(prog_file) {
+        Err(Error::CouldNotFindFlag(flag, 2)) => assert_eq!(flag, "nowhere"),
+        Err(e) => panic!("Expected unknown flag error, got : {:?}", e),
+        Ok(_) => panic!("Program should not compile"),
+    }
+    match Program::compile(parse("var: &a, int\nflg: rare\nset: &a, 2.5\n")) {
+        Err(Error::CouldNotParseIntValue(value, 2)) => assert_eq!(value, "2.5"),
+        Err(e) => panic!("Expected int parsing error, got : {:?}", e),
+        Ok(_) => panic!("Program should not compile"),
+    }
+    match Program::compile(parse(
+        "var: &c, chr\nset: &c, a\ncadd: &c, 1\nvar: &f, flt\ncmul: a, &f\n",
+    )) {
+        Err(Error::CannotDetermineReturnType(4)) => (),
+        Err(e) => panic!("Expected return type error, got : {:?}", e),
+        Ok(_) => panic!("Program should not compile"),
+    }
+}
+
+#[test]
+fn typed_literals() {
+    let mut prog_file =
+        ProgramFile::from_source("var: &a, int\nset: &a, -5\nprt: 1.2.3\nsub: &a, -\n");
+    let errors = match prog_file.parse_all() {
+        Ok(_) => panic!("Program should not parse"),
+        Err(e) => e,
+    };
+    match &errors[..] {
+        [ErrorIO::InvalidValue(value, 2)] => assert_eq!(value, "1.2.3"),
+        e => panic!("Expected invalid value error, got : {:?}", e),
+    }
+    assert_eq!(
+        prog_file.lines[1].instruction,
+        Instruction::Set {
+            var: Var::new("&a"),
+            value: Val::Const(Const::Int(-5))
+        }
+    );
+    // Parsed variables are not resolved to a slot, not even the carry one
+    match &prog_file.lines[3].instruction {
+        Instruction::Sub {
+            var,
+            value: Val::Var(carry),
+        } => assert_eq!((var.slot, carry.slot), (None, None)),
+        ins => panic!("Expected `sub`, got : {:?}", ins),
+    }
+    assert_eq!(
+        prog_file.lines[3].instruction,
+        Instruction::Sub {
+            var: Var::new("&a"),
+            value: Val::Var(Var::new("-"))
+        }
+    );
+}
+
+#[test]
+fn coerced_constants() {
+    let prog = run("var: &f, flt\nset: &f, 3\nvar: &c, chr\nset: &c, 7\n");
+    assert_eq!(prog.memory["&f"], Cll::Flt(Some(3.)));
+    assert_eq!(prog.memory["&c"], Cll::Chr(Some('7')));
+}
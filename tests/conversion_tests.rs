@@ -0,0 +1,60 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+
+#[test]
+fn conversions() {
+    let prog = run(
+        "var: &i, int\nset: &i, 7\nvar: &f, flt\nitf: &f, &i\ndiv: &f, 2\n\
+         var: &t, int\nfti: &t, &f\nvar: &r, int\nfti: &r, &f, round\nvar: &n, int\nfti: &n, -2.5, round\n\
+         var: &c, chr\nset: &c, 'A'\nvar: &p, int\ncti: &p, &c\nvar: &d, chr\nitc: &d, 955\n",
+    );
+    assert_eq!(prog.memory["&f"], Cll::Flt(Some(3.5)));
+    assert_eq!(prog.memory["&t"], Cll::Int(Some(3)));
+    assert_eq!(prog.memory["&r"], Cll::Int(Some(4)));
+    assert_eq!(prog.memory["&n"], Cll::Int(Some(-3)));
+    assert_eq!(prog.memory["&p"], Cll::Int(Some(65)));
+    assert_eq!(prog.memory["&d"], Cll::Chr(Some('λ')));
+}
+
+#[test]
+fn conversion_errors() {
+    match program("var: &f, flt\nset: &f, 0\ndiv: &f, 0\nvar: &i, int\nfti: &i, &f\n").run() {
+        Err(Error::CannotConvertToInt(val, 4)) => assert!(val.is_nan()),
+        e => panic!("Expected conversion error, got : {:?}", e),
+    }
+    match program("var: &f, flt\nset: &f, 1\ndiv: &f, 0\nvar: &i, int\nfti: &i, &f\n").run() {
+        Err(Error::CannotConvertToInt(val, 4)) => assert_eq!(val, f64::INFINITY),
+        e => panic!("Expected conversion error, got : {:?}", e),
+    }
+    match program("var: &i, int\nfti: &i, 3000000000.0\n").run() {
+        Err(Error::CannotConvertToInt(..)) => (),
+        e => panic!("Expected conversion error, got : {:?}", e),
+    }
+    match program("var: &c, chr\nitc: &c, 55296\n").run() {
+        Err(Error::InvalidCodePoint(55296, 1)) => (),
+        e => panic!("Expected invalid code point, got : {:?}", e),
+    }
+    match program("var: &c, chr\nitc: &c, -1\n").run() {
+        Err(Error::InvalidCodePoint(-1, 1)) => (),
+        e => panic!("Expected invalid code point, got : {:?}", e),
+    }
+    match program("var: &f, flt\nset: &f, 1\nvar: &i, int\nitf: &i, &f\n").run() {
+        Err(Error::VariablesDifferInType(3)) => (),
+        e => panic!("Expected type error, got : {:?}", e),
+    }
+    match Program::compile(parse("var: &f, flt\nitf: &f, 2.5\n")) {
+        Err(Error::CouldNotParseIntValue(val, 1)) => assert_eq!(val, "2.5"),
+        Err(e) => panic!("Expected int parsing error, got : {:?}", e),
+        Ok(_) => panic!("Program should not compile"),
+    }
+    match ProgramFile::from_source("var: &i, int\nfti: &i, 2.5, floor\n").parse() {
+        Err(ErrorIO::UnknownRounding(rounding, 1)) => assert_eq!(rounding, "floor"),
+        e => panic!("Expected unknown rounding, got : {:?}", e),
+    }
+    assert_eq!(
+        check_errors("var: &i, int\nvar: &f, flt\nitf: &i, &i\ncti: &i, &f\nitc: &i, 65\n"),
+        vec![("E0103", 2), ("E0103", 3), ("E0103", 4)]
+    );
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::*;
+use libmoon::debugger::Debugger;
+use libmoon::*;
+
+fn session(dbg: &mut Debugger, script: &str) -> String {
+    let mut output = Vec::new();
+    if let Err(e) = dbg.serve(script.as_bytes(), &mut output) {
+        panic!("Error during debugging : {:?}", e);
+    }
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn breakpoints_and_watchpoints() {
+    let mut dbg = Debugger::new(quiet(compile(open("tests/test_programs/tables.moon"))));
+    let output = session(
+        &mut dbg,
+        "break table2\ncontinue\ncontinue\nprint &b\ndelete table2\nwatch &a\ncontinue\nmemory\nquit\nstep\n",
+    );
+    assert_eq!(
+        output,
+        "breakpoint at line 8
+breakpoint, line 8 : flg: table2
+breakpoint, line 8 : flg: table2
+&b = 1     _int
+deleted breakpoint at line 8
+watching `&a`
+watchpoint `&a` at line 20 : 0     _int -> 1     _int
+line 21 : 
+&a = 1     _int
+&b = 0     _int
+- = 0     _int
+"
+    );
+    assert_eq!(dbg.program().lnb, 20);
+}
+
+#[test]
+fn step_over_calls() {
+    let mut dbg = Debugger::new(quiet(compile(open("tests/test_programs/subroutines.moon"))));
+    let output = session(
+        &mut dbg,
+        "n\nn\nnext\nstep\nwhere\nbreak 9\ncontinue\nc\nc\nc\n",
+    );
+    assert_eq!(
+        output,
+        "line 2 : set: &n, 3
+line 3 : cal: countdown
+line 4 : prt: &n
+line 5 : gto: end
+line 5 : gto: end
+breakpoint at line 9
+program finished at line 16
+program finished at line 16
+program finished at line 16
+program finished at line 16
+"
+    );
+    assert_eq!(dbg.program().memory["&n"], Cll::Int(Some(0)));
+}
+
+#[test]
+fn watchpoints_on_last_instruction() {
+    let mut dbg = Debugger::new(program("var: &a, int\nset: &a, 1\n"));
+    let output = session(&mut dbg, "step\nwatch &a\ncontinue\n");
+    // The change is reported along with the end of the program
+    assert_eq!(
+        output,
+        "line 2 : set: &a, 1
+watching `&a`
+watchpoint `&a` at line 2 : unitialized _int -> 1     _int
+program finished at line 3
+"
+    );
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::*;
+use libmoon::diagnostics::Located;
+use libmoon::*;
+
+#[test]
+fn parse_error_diagnostic() {
+    let mut prog_file = ProgramFile::from_source("var: &a, int\nvar: &b,  str\n");
+    let error = match prog_file.parse() {
+        Ok(_) => panic!("Program should not parse"),
+        Err(e) => e,
+    };
+    assert_eq!(
+        error.to_string(),
+        "unknown type `str`, expected `int`, `flt` or `chr` at line 2"
+    );
+    assert_eq!(
+        prog_file.render(&error),
+        "error[E0011]: unknown type `str`, expected `int`, `flt` or `chr`
+ --> <source>:2:11
+  |
+2 | var: &b,  str
+  |           ^^^
+"
+    );
+}
+
+#[test]
+fn arity_diagnostic() {
+    let mut prog_file = ProgramFile::from_source("var: &a, int\nset: &a, 1, 2\n");
+    let error = match prog_file.parse() {
+        Ok(_) => panic!("Program should not parse"),
+        Err(e) => e,
+    };
+    assert_eq!(
+        prog_file.render(&error),
+        "error[E0006]: too many operands, `set` expects 2 operands
+ --> <source>:2:13
+  |
+2 | set: &a, 1, 2
+  |             ^
+"
+    );
+    let mut prog_file = ProgramFile::from_source("var: &a, int\njne: &a\nprt:\n");
+    let errors = match prog_file.parse_all() {
+        Ok(_) => panic!("Program should not parse"),
+        Err(e) => e,
+    };
+    let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "not enough operands, `jne` expects 2 or 3 operands at line 2",
+            "not enough operands, `prt` expects 1 operand at line 3",
+        ]
+    );
+}
+
+#[test]
+fn runtime_error_diagnostic() {
+    let prog_file = open("tests/test_programs/basic.moon");
+    assert_eq!(prog_file.lines[2].span.operands, vec![6..8, 10..12]);
+
+    let mut prog = program("var: &a, int\nset: &a, 1\nadd: &a, &b\n");
+    let error = match prog.run() {
+        Ok(_) => panic!("Program should fail"),
+        Err(e) => e,
+    };
+    assert_eq!(error.line(), 2);
+    // Compiled lines keep their spans
+    assert_eq!(prog.code()[2].span.operands, vec![5..7, 9..11]);
+    assert_eq!(
+        prog.render(&error),
+        "error[E0102]: variable `&b` does not exist
+ --> <source>:3:10
+  |
+3 | add: &a, &b
+  |          ^^
+"
+    );
+}
+
+#[test]
+fn tab_indented_diagnostic() {
+    let mut prog = program("var: &a, int\n\tset: &a, 1\n\tadd: &a, &b\n");
+    let error = match prog.run() {
+        Ok(_) => panic!("Program should fail"),
+        Err(e) => e,
+    };
+    assert_eq!(
+        prog.render(&error),
+        "error[E0102]: variable `&b` does not exist
+ --> <source>:3:11
+  |
+3 | \tadd: &a, &b
+  | \t         ^^
+"
+    );
+}
+
+#[test]
+fn moon_error() -> Result<(), Box<dyn std::error::Error>> {
+    load_and_run(include_str!("test_programs/basic.moon"))?;
+
+    let error = match load_and_run("var: &a, int\nprt: &b\n") {
+        Ok(_) => panic!("Program should fail"),
+        Err(e) => e,
+    };
+    assert_eq!(error.code(), "E0102");
+    assert_eq!(
+        error.to_string(),
+        "runtime error : variable `&b` does not exist at line 2"
+    );
+    match load_and_run("var: &a\n") {
+        Err(MoonError::Parse(ErrorIO::NotEnoughOperands(_, _, 0))) => (),
+        e => panic!("Expected parse error, got : {:?}", e),
+    }
+    Ok(())
+}
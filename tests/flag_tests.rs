@@ -0,0 +1,100 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+
+#[test]
+fn duplicate_flags() {
+    let mut prog_file = ProgramFile::from_source("flg: loop\nnll: nll\nflg: loop\ngto: loop\n");
+    match prog_file.parse() {
+        Err(ErrorIO::DuplicateFlag(flag, 0, 2)) => assert_eq!(flag, "loop"),
+        e => panic!("Expected duplicate flag error, got : {:?}", e),
+    }
+    // Parsing stops at the duplicate flag
+    assert_eq!(prog_file.lines.len(), 2);
+    // Without stopping, the duplicate flag is replaced like other lines with errors
+    if prog_file.parse_all().is_ok() {
+        panic!("Program should not parse")
+    }
+    assert_eq!(prog_file.lines.len(), 4);
+    assert_eq!(
+        prog_file.lines[2].instruction,
+        instructions::Instruction::Nll
+    );
+    assert_eq!(prog_file.flags["loop"], 0);
+    assert_eq!(
+        ErrorIO::DuplicateFlag("loop".to_owned(), 0, 2).to_string(),
+        "flag `loop` redefined at line 3 (first created on line 1)"
+    );
+    assert_eq!(
+        prog_file.render(&ErrorIO::DuplicateFlag("loop".to_owned(), 0, 2)),
+        "error[E0014]: flag `loop` redefined (first created on line 1)
+ --> <source>:3:6
+  |
+3 | flg: loop
+  |      ^^^^
+"
+    );
+}
+
+#[test]
+fn undefined_flags() {
+    let mut prog_file =
+        ProgramFile::from_source("var: &a, int\nflg: start\njmp: &a, strat\ngto: end\n");
+    let errors = match prog_file.parse_all() {
+        Ok(_) => panic!("Program should not parse"),
+        Err(e) => e,
+    };
+    match &errors[..] {
+        [ErrorIO::UndefinedFlag(first, 2), ErrorIO::UndefinedFlag(second, 3)] => {
+            assert_eq!(first, "strat");
+            assert_eq!(second, "end");
+        }
+        e => panic!("Expected undefined flag errors, got : {:?}", e),
+    }
+    assert_eq!(
+        errors[0].to_string(),
+        "flag `strat` is never created at line 3"
+    );
+}
+
+#[test]
+fn unused_flags() {
+    let prog_file = open("tests/test_programs/subroutines.moon");
+    assert_eq!(prog_file.warnings, vec![]);
+    assert_eq!(
+        prog_file.flag_sites(),
+        vec![
+            FlagSites {
+                name: "countdown".to_owned(),
+                definition: 6,
+                references: vec![2, 10],
+            },
+            FlagSites {
+                name: "done".to_owned(),
+                definition: 11,
+                references: vec![9],
+            },
+            FlagSites {
+                name: "end".to_owned(),
+                definition: 14,
+                references: vec![4],
+            },
+        ]
+    );
+
+    let prog_file = parse("flg: start\nflg: unused\ngto: start\n");
+    assert_eq!(
+        prog_file.warnings,
+        vec![Warning::UnusedFlag("unused".to_owned(), 1)]
+    );
+    assert_eq!(
+        prog_file.render(&prog_file.warnings[0]),
+        "warning[W0001]: flag `unused` is never used
+ --> <source>:2:6
+  |
+2 | flg: unused
+  |      ^^^^^^
+"
+    );
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+use std::time::{Duration, Instant};
+
+#[test]
+fn fuel_exhausted() {
+    // `import.moon` loops forever
+    let mut prog = compile(open("tests/test_programs/import.moon")).with_fuel(100);
+    match prog.run() {
+        Err(Error::FuelExhausted {
+            executed: 100,
+            line: 4,
+        }) => (),
+        e => panic!("Expected exhausted fuel, got : {:?}", e),
+    }
+    assert_eq!(prog.memory["a"], Cll::Int(Some(10 + 5 * 32)));
+
+    prog.add_fuel(3);
+    match prog.run() {
+        Err(Error::FuelExhausted {
+            executed: 103,
+            line: 4,
+        }) => (),
+        e => panic!("Expected exhausted fuel, got : {:?}", e),
+    }
+    assert_eq!(prog.memory["a"], Cll::Int(Some(10 + 5 * 33)));
+    assert_eq!(prog.fuel(), Some(0));
+}
+
+#[test]
+fn unlimited_fuel() {
+    let mut prog = program("var: &a, int\nset: &a, 1\n").with_fuel(10);
+    // Adding fuel saturates instead of overflowing
+    prog.add_fuel(u64::MAX);
+    assert_eq!(prog.fuel(), Some(u64::MAX));
+    prog.add_fuel(u64::MAX);
+    assert_eq!(prog.fuel(), Some(u64::MAX));
+    prog = finish(prog);
+    assert_eq!(prog.fuel(), Some(u64::MAX - 2));
+}
+
+#[test]
+fn deadline_exceeded() {
+    let mut prog = compile(open("tests/test_programs/import.moon"))
+        .with_deadline(Instant::now() + Duration::from_millis(20));
+    match prog.run() {
+        Err(Error::DeadlineExceeded { executed, .. }) => assert_eq!(prog.executed(), executed),
+        e => panic!("Expected exceeded deadline, got : {:?}", e),
+    }
+}
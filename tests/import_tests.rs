@@ -1,79 +1,112 @@
+mod common;
+
+use common::*;
 use libmoon::*;
 
 #[test]
 fn import() {
-    let mut prog = ProgramFile::new();
-    match prog.open("tests/test_programs/import.moon") {
-        Ok(_) => println!("File opened !"),
-        Err(e) => panic!("Error : {:?}", e),
-    };
-    match prog.parse() {
-        Ok(_) => println!("Program parsed !"),
-        Err(e) => panic!("Error : {:?}", e),
-    }
+    let prog = open("tests/test_programs/import.moon");
     assert_eq!(
-        prog.lines[0],
+        prog.lines[0].instruction,
         instructions::Instruction::Var {
-            var: "a".to_owned(),
+            var: instructions::Var::new("a"),
             var_type: instructions::Type::Int
         }
     );
     assert_eq!(
-        prog.lines[1],
+        prog.lines[1].instruction,
         instructions::Instruction::Set {
-            var: "a".to_owned(),
-            value: instructions::Val::Value("10".to_owned())
+            var: instructions::Var::new("a"),
+            value: instructions::Val::Const(instructions::Const::Int(10))
         }
     );
-    assert_eq!(prog.lines[2], instructions::Instruction::Nll);
-    assert_eq!(prog.lines[3], instructions::Instruction::Flg);
+    assert_eq!(prog.lines[2].instruction, instructions::Instruction::Nll);
+    assert_eq!(prog.lines[3].instruction, instructions::Instruction::Flg);
     assert_eq!(
-        prog.lines[4],
+        prog.lines[4].instruction,
         instructions::Instruction::Add {
-            var: "a".to_owned(),
-            value: instructions::Val::Value("5".to_owned())
+            var: instructions::Var::new("a"),
+            value: instructions::Val::Const(instructions::Const::Int(5))
         }
     );
     assert_eq!(
-        prog.lines[5],
+        prog.lines[5].instruction,
         instructions::Instruction::Gto {
-            flag: "hello".to_owned()
+            flag: instructions::Flag::new("hello")
         }
     );
 }
 
 #[test]
 fn basic_run() {
-    let mut prog_file = ProgramFile::new();
-    match prog_file.open("tests/test_programs/basic.moon") {
-        Ok(_) => println!("File opened !"),
-        Err(e) => panic!("Error during opening : {:?}", e),
+    finish(compile(open("tests/test_programs/basic.moon")));
+}
+
+#[test]
+fn tables() {
+    finish(compile(open("tests/test_programs/tables.moon")));
+}
+
+#[test]
+fn from_source() {
+    let prog_file = parse(include_str!("test_programs/basic.moon"));
+    assert_eq!(prog_file.lines.len(), 5);
+}
+
+#[test]
+fn from_reader() {
+    let source: &[u8] = b"var: &a, int\nset: &a, 3\n";
+    let mut prog_file = match ProgramFile::from_reader(source) {
+        Ok(f) => f,
+        Err(e) => panic!("Error during reading : {:?}", e),
     };
     match prog_file.parse() {
         Ok(_) => println!("Program parsed !"),
         Err(e) => panic!("Error during parsing : {:?}", e),
     }
-    let mut prog = Program::from(prog_file);
-    match prog.run() {
-        Ok(l) => println!("Program finished successfuly at l°{}", l),
-        Err(e) => panic!("Error during runtime : {:?}", e),
-    };
+    assert_eq!(prog_file.lines.len(), 2);
 }
 
 #[test]
-fn tables() {
-    let mut prog_file = ProgramFile::new();
-    match prog_file.open("tests/test_programs/tables.moon") {
-        Ok(_) => println!("File opened !"),
+fn read_errors() {
+    let source: &[u8] = b"var: &a, int\nset: &a, \xff\n";
+    match ProgramFile::from_reader(source) {
+        Err(ErrorIO::InvalidUtf8(22)) => (),
+        e => panic!("Expected invalid UTF-8 error, got : {:?}", e),
+    }
+    match ProgramFile::open("tests/test_programs/does_not_exist.moon") {
+        Err(e @ ErrorIO::CannotReadFile(..)) => {
+            use std::error::Error;
+            match e.source().and_then(|e| e.downcast_ref::<std::io::Error>()) {
+                Some(e) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+                None => panic!("Expected the io error as source, got : {:?}", e),
+            }
+        }
+        e => panic!("Expected unreadable file error, got : {:?}", e),
+    }
+}
+
+#[test]
+fn parse_all_errors() {
+    let mut prog_file = match ProgramFile::open("tests/test_programs/errors.moon") {
+        Ok(f) => f,
         Err(e) => panic!("Error during opening : {:?}", e),
     };
-    match prog_file.parse() {
-        Ok(_) => println!("Program parsed !"),
-        Err(e) => panic!("Error during parsing : {:?}", e),
-    }
-    let mut prog = Program::from(prog_file);
-    match prog.run() {
-        Ok(l) => println!("Program finished successfuly at l°{}", l),
-        Err(e) => panic!("Error during runtime : {:?}", e),
+    let errors = match prog_file.parse_all() {
+        Ok(_) => panic!("Program should not parse"),
+        Err(e) => e,
     };
+    let lines: Vec<Option<usize>> = errors.iter().map(|e| e.line()).collect();
+    assert_eq!(lines, vec![Some(2), Some(3), Some(4), Some(6)]);
+    assert_eq!(prog_file.lines.len(), 8);
+    assert_eq!(
+        prog_file.lines[3].instruction,
+        instructions::Instruction::Nll
+    );
+    assert_eq!(
+        prog_file.lines[7].instruction,
+        instructions::Instruction::Prt {
+            value: instructions::Val::Var(instructions::Var::new("&a"))
+        }
+    );
 }
@@ -0,0 +1,41 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn run_with_input(input: &'static str) -> (Result<State, Error>, Vec<String>) {
+    let printed = Rc::new(RefCell::new(Vec::new()));
+    let sink = printed.clone();
+    let mut prog = compile(open("tests/test_programs/input.moon"))
+        .with_input(input.as_bytes())
+        .with_output(move |_: usize, _: Option<&str>, value: &Cll| {
+            sink.borrow_mut().push(value.plain());
+            Ok(())
+        });
+    let result = prog.run();
+    let printed = printed.borrow().clone();
+    (result, printed)
+}
+
+#[test]
+fn scripted_input() {
+    let (result, printed) = run_with_input("12\n 30 \n \n");
+    if let Err(e) = result {
+        panic!("Error during runtime : {:?}", e);
+    }
+    assert_eq!(printed, vec!["42".to_owned(), " ".to_owned()]);
+}
+
+#[test]
+fn input_errors() {
+    match run_with_input("12\nabc\nx\n").0 {
+        Err(Error::CouldNotParseIntValue(text, 4)) => assert_eq!(text, "abc"),
+        e => panic!("Expected parse error, got : {:?}", e),
+    }
+    match run_with_input("12\n").0 {
+        Err(Error::EndOfInput(4)) => (),
+        e => panic!("Expected end of input, got : {:?}", e),
+    }
+}
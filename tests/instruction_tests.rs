@@ -0,0 +1,27 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+
+#[test]
+fn inc_dec_del() {
+    let prog = run("var: &i, int\nset: &i, 5\ninc: &i\ninc: &i\ndec: &i\n\
+                    var: &f, flt\nset: &f, 0.5\ndec: &f\n\
+                    var: &d, int\ndel: &d\n");
+    assert_eq!(prog.memory["&i"].plain(), "6");
+    assert_eq!(prog.memory["&f"].plain(), "-0.5");
+    assert!(!prog.memory.contains_key("&d"));
+
+    match program("var: &c, chr\nset: &c, a\ninc: &c\n").run() {
+        Err(Error::CannotApplyOperationsOnChar(2)) => (),
+        e => panic!("Expected char error, got : {:?}", e),
+    }
+    match program("var: &i, int\ndec: &i\n").run() {
+        Err(Error::VariableIsUninitialized(name, 1)) => assert_eq!(name, "&i"),
+        e => panic!("Expected uninitialized error, got : {:?}", e),
+    }
+    match program("var: &i, int\nset: &i, 1\ndel: &i\ninc: &i\n").run() {
+        Err(Error::VariableDoesNotExists(name, 3)) => assert_eq!(name, "&i"),
+        e => panic!("Expected missing variable error, got : {:?}", e),
+    }
+}
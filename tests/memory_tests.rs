@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts the allocations made by each thread, so that tests running in parallel don't add up
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|n| n.get())
+}
+
+/// Declares `variables` variables, pauses, then runs a loop of `set` and arithmetic instructions
+fn paused_loop(variables: usize) -> Program {
+    let mut source = String::new();
+    for n in 0..variables {
+        source += &format!("var: &v{}, int\nset: &v{}, {}\n", n, n, n);
+    }
+    source += "var: &i, int\nset: &i, 0\npas:\n";
+    source += "flg: loop\nset: &v0, &i\nadd: &v0, &v1\nmul: &v0, 2\ninc: &i\ncsub: 2000, &i\njne: -, loop\n";
+
+    let mut prog = program(&source);
+    match prog.run() {
+        Ok(State::Paused(_)) => prog,
+        e => panic!("Expected the program to pause, got : {:?}", e),
+    }
+}
+
+/// Runs the loop, and returns the number of instructions it executed and the allocations it made
+fn run_loop(variables: usize) -> (u64, usize) {
+    let mut prog = paused_loop(variables);
+    let executed = prog.executed();
+    let before = allocations();
+    let state = prog.resume();
+    let allocated = allocations() - before;
+    match state {
+        Ok(s) => println!("Program finished successfuly : {:?}", s),
+        Err(e) => panic!("Error during runtime : {:?}", e),
+    };
+    assert_eq!(prog.memory["&v0"], Cll::Int(Some(2 * (1999 + 1))));
+    assert_eq!(prog.memory.iter().count(), variables + 2);
+    (prog.executed() - executed, allocated)
+}
+
+#[test]
+fn many_variables() {
+    let (few, few_allocated) = run_loop(10);
+    let (many, many_allocated) = run_loop(10_000);
+    // The number of variables only changes the memory, not the loop
+    assert_eq!(few, 2000 * 7);
+    assert_eq!(many, few);
+    // Copying the memory would allocate for each variable on each instruction
+    assert_eq!(few_allocated, 0);
+    assert_eq!(many_allocated, 0);
+}
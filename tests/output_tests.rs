@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// A `Write` sharing its buffer, so the output can be read back after the run
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn plain_output() {
+    let buffer = SharedBuffer::default();
+    finish(
+        compile(open("tests/test_programs/basic.moon"))
+            .with_writer(buffer.clone(), OutputFormat::Plain),
+    );
+    assert_eq!(String::from_utf8_lossy(&buffer.0.borrow()), "15\n5\n");
+}
+
+#[test]
+fn debug_output() {
+    let buffer = SharedBuffer::default();
+    finish(
+        program("var: &a, flt\nset: &a, 2.5\nprt: &a\nprt: 7\n")
+            .with_writer(buffer.clone(), OutputFormat::Debug),
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&buffer.0.borrow()),
+        "l° 2 : &a    =   2.5   _flt\nl° 3 : value =   7\n"
+    );
+}
+
+#[test]
+fn callback_output() {
+    let printed = Rc::new(RefCell::new(Vec::new()));
+    let sink = printed.clone();
+    finish(
+        compile(open("tests/test_programs/tables.moon")).with_output(
+            move |_line: usize, _name: Option<&str>, value: &Cll| {
+                sink.borrow_mut().push(value.plain());
+                Ok(())
+            },
+        ),
+    );
+    let printed = printed.borrow();
+    assert_eq!(printed.len(), 121);
+    assert_eq!(printed[11..13], ["0".to_owned(), "1".to_owned()]);
+    assert_eq!(printed[120], "100");
+}
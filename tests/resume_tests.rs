@@ -0,0 +1,31 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+
+#[test]
+fn pause_and_resume() {
+    let mut prog = quiet(compile(open("tests/test_programs/generator.moon")));
+
+    let mut states = match prog.run() {
+        Ok(state) => vec![state],
+        Err(e) => panic!("Error during runtime : {:?}", e),
+    };
+    while let Some(State::Yielded(_)) | Some(State::Paused(_)) = states.last() {
+        match prog.resume() {
+            Ok(state) => states.push(state),
+            Err(e) => panic!("Error during runtime : {:?}", e),
+        }
+    }
+    assert_eq!(
+        states,
+        vec![
+            State::Yielded(Cll::Int(Some(1))),
+            State::Yielded(Cll::Int(Some(2))),
+            State::Yielded(Cll::Int(Some(3))),
+            State::Paused(7),
+            State::Finished(9),
+        ]
+    );
+    assert_eq!(prog.memory["&i"], Cll::Int(Some(3)));
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+
+#[test]
+fn push_and_pop() {
+    let prog = run(include_str!("test_programs/stack.moon"));
+    assert_eq!(prog.memory["&a"].plain(), "7");
+    assert_eq!(prog.memory["&b"].plain(), "1.5");
+    assert_eq!(prog.stack().len(), 1);
+    assert_eq!(prog.stack()[0].plain(), "3");
+}
+
+#[test]
+fn stack_errors() {
+    match program("var: &a, int\npop: &a\n").run() {
+        Err(Error::StackUnderflow(1)) => (),
+        e => panic!("Expected stack underflow, got : {:?}", e),
+    }
+    match program("var: &a, chr\npush: 1\npop: &a\n").run() {
+        Err(Error::VariablesDifferInType(2)) => (),
+        e => panic!("Expected type error, got : {:?}", e),
+    }
+    let mut prog = program("push: 1\npush: 2\npush: 3\n").with_max_stack_depth(2);
+    match prog.run() {
+        Err(Error::StackOverflow(2, 2)) => (),
+        e => panic!("Expected stack overflow, got : {:?}", e),
+    }
+    assert_eq!(prog.stack().len(), 2);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::*;
+use libmoon::instructions::Instruction;
+use libmoon::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn single_steps() {
+    let mut prog = quiet(compile(open("tests/test_programs/subroutines.moon")));
+    let mut steps = Vec::new();
+    loop {
+        match prog.step() {
+            Ok(step) if step.state.is_some() => break,
+            Ok(step) => steps.push(step),
+            Err(e) => panic!("Error during runtime : {:?}", e),
+        }
+    }
+    assert_eq!(
+        steps[1],
+        Step {
+            line: 1,
+            next: 2,
+            written: vec!["&n".to_owned()],
+            jump: None,
+            state: None,
+        }
+    );
+    assert_eq!(steps[2].jump, Some(6));
+    assert_eq!(steps[2].written, Vec::<String>::new());
+    assert_eq!(prog.lnb, 15);
+    assert_eq!(prog.step().unwrap().state, Some(State::Finished(15)));
+}
+
+#[test]
+fn jumps_to_the_next_line() {
+    let mut prog = program(
+        "var: &a, int\nset: &a, 0\njmp: &a, next\nflg: next\njne: &a, last\ngto: last\nflg: last\n",
+    );
+    let mut jumps = Vec::new();
+    loop {
+        match prog.step() {
+            Ok(step) if step.state.is_some() => break,
+            Ok(step) => jumps.push((step.line, step.jump)),
+            Err(e) => panic!("Error during runtime : {:?}", e),
+        }
+    }
+    // Taken jumps are reported even when they lead to the following line
+    assert_eq!(
+        jumps,
+        vec![
+            (0, None),
+            (1, None),
+            (2, Some(3)),
+            (3, None),
+            (4, None),
+            (5, Some(6)),
+        ]
+    );
+}
+
+/// Records every executed line, along with the value of `&n` before it
+struct Tracer(Rc<RefCell<Vec<(usize, String)>>>);
+
+impl Hook for Tracer {
+    fn before(&mut self, program: &Program, line: usize, instruction: &Instruction) {
+        if let Instruction::Sub { .. } = instruction {
+            let n = program.memory["&n"].plain();
+            self.0.borrow_mut().push((line, n));
+        }
+    }
+
+    fn after(&mut self, program: &Program, step: &Step) {
+        assert_eq!(program.lnb, step.next);
+    }
+}
+
+#[test]
+fn hooks() {
+    let trace = Rc::new(RefCell::new(Vec::new()));
+    finish(
+        quiet(compile(open("tests/test_programs/subroutines.moon")))
+            .with_hook(Tracer(trace.clone())),
+    );
+    assert_eq!(
+        *trace.borrow(),
+        vec![
+            (8, "3".to_owned()),
+            (8, "2".to_owned()),
+            (8, "1".to_owned())
+        ]
+    );
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::*;
+use libmoon::instructions::Arity;
+use libmoon::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn call_and_return() {
+    let printed = Rc::new(RefCell::new(Vec::new()));
+    let sink = printed.clone();
+    let prog = finish(
+        program(include_str!("test_programs/subroutines.moon")).with_output(
+            move |_: usize, _: Option<&str>, value: &Cll| {
+                sink.borrow_mut().push(value.plain());
+                Ok(())
+            },
+        ),
+    );
+    assert_eq!(*printed.borrow(), vec!["3", "2", "1", "0"]);
+    assert!(prog.call_stack().is_empty());
+}
+
+#[test]
+fn call_errors() {
+    let mut prog = program(include_str!("test_programs/subroutines.moon")).with_max_call_depth(2);
+    match prog.run() {
+        Err(Error::CallStackOverflow(2, 10)) => (),
+        e => panic!("Expected call stack overflow, got : {:?}", e),
+    }
+    assert_eq!(prog.call_stack(), &[3, 11]);
+
+    match program("nll: empty\nret:\n").run() {
+        Err(Error::ReturnOutsideCall(1)) => (),
+        e => panic!("Expected return outside call, got : {:?}", e),
+    }
+    let mut prog_file = ProgramFile::from_source("ret: 1\n");
+    match prog_file.parse() {
+        Err(ErrorIO::TooMuchOperands(ins, Arity { min: 0, max: 0 }, 0)) => assert_eq!(ins, "ret"),
+        e => panic!("Expected too much operands, got : {:?}", e),
+    }
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::*;
+use libmoon::*;
+
+#[test]
+fn three_address_arithmetic() {
+    let prog = run(
+        "var: &a, int\nset: &a, 7\nvar: &b, int\nset: &b, 2\ncadd: &a, 100\n\
+         var: &s, int\nadd: &s, &a, &b\nvar: &d, int\nsub: &d, 10, &a\nmod: &a, &a, &b\n\
+         var: &f, flt\nset: &f, 1.5\nvar: &g, flt\nmul: &g, &f, 3\n",
+    );
+    assert_eq!(prog.memory["&s"], Cll::Int(Some(9)));
+    assert_eq!(prog.memory["&d"], Cll::Int(Some(3)));
+    assert_eq!(prog.memory["&a"], Cll::Int(Some(1)));
+    assert_eq!(prog.memory["&b"], Cll::Int(Some(2)));
+    assert_eq!(prog.memory["&g"], Cll::Flt(Some(4.5)));
+    // The carry is left unchanged
+    assert_eq!(prog.memory["-"], Cll::Int(Some(107)));
+}
+
+#[test]
+fn three_address_errors() {
+    match program("var: &a, int\nset: &a, 1\nvar: &f, flt\nadd: &f, &a, 2\n").run() {
+        Err(Error::VariablesDifferInType(3)) => (),
+        e => panic!("Expected type error, got : {:?}", e),
+    }
+    match program("var: &a, int\nset: &a, 1\nadd: &b, &a, 2\n").run() {
+        Err(Error::VariableDoesNotExists(var, 2)) => assert_eq!(var, "&b"),
+        e => panic!("Expected missing variable error, got : {:?}", e),
+    }
+    match program("var: &a, int\ndiv: &a, 1, 2\n").run() {
+        Err(Error::CannotDetermineReturnType(1)) => (),
+        e => panic!("Expected return type error, got : {:?}", e),
+    }
+    match Program::compile(parse("var: &a, int\nset: &a, 1\nmul: &a, 2.5, &a\n")) {
+        Err(Error::CannotDetermineReturnType(2)) => (),
+        Err(e) => panic!("Expected return type error, got : {:?}", e),
+        Ok(_) => panic!("Program should not compile"),
+    }
+    assert_eq!(
+        check_errors(
+            "var: &a, int\nvar: &f, flt\nadd: &f, &a, 1\nvar: &c, chr\nsub: &a, &c, &c\nadd: &a, &a, 1\n"
+        ),
+        vec![("E0103", 2), ("E0108", 4)]
+    );
+}